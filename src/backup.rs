@@ -0,0 +1,609 @@
+// src/backup.rs
+
+use crate::config::{self, Config};
+use crate::login::account_exists;
+use crate::session;
+use crate::store::{BlueBorderContainer, BlueButton, GreenButton};
+use crate::vault::user_dir;
+use age::secrecy::SecretString;
+use age::{Decryptor, Encryptor};
+use dirs::config_dir;
+use hex::{decode, encode};
+use iced::{
+    alignment, button, text_input, Alignment, Button, Checkbox, Color, Column, Container, Element,
+    Image, Length, Text, TextInput,
+};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Bump when the archive layout changes
+const BACKUP_VERSION: u32 = 1;
+
+// Where the account record lives, relative to the working directory
+const ACCOUNT_FILE: &str = "auth/authentication.json";
+
+// The key encrypting the account record, inside the lockbox directory
+const SECRET_KEY_FILE: &str = "secret_key";
+
+// Root a backed up file is restored relative to
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+enum BackupRoot {
+    // The working directory holding the account record
+    Account,
+    // The lockbox directory inside the platform config directory
+    Config,
+}
+
+// Where a restored file goes
+#[derive(Debug, PartialEq)]
+enum Destination {
+    // A single file, relative to its root
+    File(BackupRoot, PathBuf),
+    // A file inside the user's vault directory, relative to it
+    Vault(PathBuf),
+}
+
+#[derive(Serialize, Deserialize)]
+struct BackupFile {
+    root: BackupRoot,
+    path: String,
+    contents: String,
+}
+
+// Everything needed to rebuild a profile on another machine
+#[derive(Serialize, Deserialize)]
+struct BackupArchive {
+    version: u32,
+    username: String,
+    created: u64,
+    files: Vec<BackupFile>,
+}
+
+fn lockbox_dir() -> Result<PathBuf, String> {
+    let config_dir = config_dir().ok_or("Failed to get config directory")?;
+    Ok(config_dir.join("lockbox"))
+}
+
+fn root_dir(root: BackupRoot) -> Result<PathBuf, String> {
+    match root {
+        BackupRoot::Account => Ok(PathBuf::from(".")),
+        BackupRoot::Config => lockbox_dir(),
+    }
+}
+
+// Collect the account record, secret key and the user's vault into an archive
+fn collect(username: &str) -> Result<BackupArchive, String> {
+    let mut files = Vec::new();
+
+    let mut add = |root: BackupRoot, path: &Path| -> Result<(), String> {
+        let base = root_dir(root)?;
+        let contents =
+            fs::read(base.join(path)).map_err(|e| format!("Failed to read {:?}: {:?}", path, e))?;
+        files.push(BackupFile {
+            root,
+            path: path.to_string_lossy().replace('\\', "/"),
+            contents: encode(contents),
+        });
+        Ok(())
+    };
+
+    add(BackupRoot::Account, Path::new(ACCOUNT_FILE))?;
+    add(BackupRoot::Config, Path::new(SECRET_KEY_FILE))?;
+
+    // Settings only exist once the user changed one
+    let lockbox_dir = lockbox_dir()?;
+    if lockbox_dir.join(config::FILE_NAME).exists() {
+        add(BackupRoot::Config, Path::new(config::FILE_NAME))?;
+    }
+
+    // The vault directory holds the identity and every encrypted record
+    let vault_dir = user_dir(username)?;
+    let mut pending = vec![vault_dir];
+    while let Some(dir) = pending.pop() {
+        let entries =
+            fs::read_dir(&dir).map_err(|e| format!("Failed to read {:?}: {:?}", dir, e))?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if let Ok(relative) = path.strip_prefix(&lockbox_dir) {
+                add(BackupRoot::Config, relative)?;
+            }
+        }
+    }
+
+    Ok(BackupArchive {
+        version: BACKUP_VERSION,
+        username: username.to_string(),
+        created: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default(),
+        files,
+    })
+}
+
+// Write an encrypted backup of a user's profile to `destination`
+pub fn export(username: &str, passphrase: &str, destination: &Path) -> Result<usize, String> {
+    let archive = collect(username)?;
+    let count = archive.files.len();
+    let plaintext =
+        serde_json::to_vec(&archive).map_err(|e| format!("Failed to build backup: {:?}", e))?;
+
+    let encryptor = Encryptor::with_user_passphrase(SecretString::new(passphrase.to_string()));
+    let mut encrypted = Vec::new();
+    let mut writer = encryptor
+        .wrap_output(&mut encrypted)
+        .map_err(|e| format!("Failed to create encryptor: {:?}", e))?;
+    writer
+        .write_all(&plaintext)
+        .map_err(|e| format!("Failed to encrypt backup: {:?}", e))?;
+    writer
+        .finish()
+        .map_err(|e| format!("Failed to finalize encryption: {:?}", e))?;
+
+    fs::write(destination, encrypted).map_err(|e| format!("Failed to write backup: {:?}", e))?;
+    Ok(count)
+}
+
+// Decrypt a backup and rebuild the profile it contains, returning the username
+pub fn restore(source: &Path, passphrase: &str, replace: bool) -> Result<String, String> {
+    let encrypted = fs::read(source).map_err(|e| format!("Failed to read backup: {:?}", e))?;
+    let decryptor = match Decryptor::new(encrypted.as_slice()) {
+        Ok(Decryptor::Passphrase(decryptor)) => decryptor,
+        Ok(_) => return Err("Not a lockbox backup".to_string()),
+        Err(e) => return Err(format!("Failed to create decryptor: {:?}", e)),
+    };
+
+    let mut plaintext = Vec::new();
+    decryptor
+        .decrypt(&SecretString::new(passphrase.to_string()), None)
+        .and_then(|mut reader| Ok(reader.read_to_end(&mut plaintext)?))
+        .map_err(|_| "Wrong backup passphrase or corrupted backup".to_string())?;
+
+    let archive: BackupArchive = serde_json::from_slice(&plaintext)
+        .map_err(|e| format!("Failed to parse backup: {:?}", e))?;
+    if archive.version != BACKUP_VERSION {
+        return Err(format!("Unsupported backup version {}", archive.version));
+    }
+
+    // Never clobber an existing profile unless the user asked for it
+    if account_exists() && !replace {
+        return Err("An account already exists on this machine".to_string());
+    }
+
+    // The username names the vault directory cleared below, so it must be a single name
+    let mut components = Path::new(&archive.username).components();
+    if !matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(_)), None)
+    ) {
+        return Err(format!(
+            "Backup has an invalid username: {}",
+            archive.username
+        ));
+    }
+
+    // Validate every path before writing anything
+    let mut files = Vec::new();
+    let mut vault_files = Vec::new();
+    let mut restores_config = false;
+    for file in &archive.files {
+        let contents = decode(&file.contents)
+            .map_err(|_| format!("Backup entry {} is corrupted", file.path))?;
+        match destination(file, &archive.username)? {
+            Destination::File(root, relative) => {
+                if relative == Path::new(config::FILE_NAME) {
+                    check_config(&contents)?;
+                    restores_config = true;
+                }
+                files.push((root_dir(root)?.join(relative), contents));
+            }
+            Destination::Vault(relative) => vault_files.push((relative, contents)),
+        }
+    }
+
+    // Stage everything next to its target so a failed write leaves the old profile intact
+    let vault_dir = user_dir(&archive.username)?;
+    let staging = vault_dir.with_file_name(format!(".{}.restore", archive.username));
+    if let Err(e) = stage(&staging, &vault_files, &files) {
+        let _ = fs::remove_dir_all(&staging);
+        for (path, _) in &files {
+            let _ = fs::remove_file(path.with_extension("tmp"));
+        }
+        return Err(e);
+    }
+
+    // Records the backup does not have must not survive into the restored vault
+    let previous = vault_dir.with_file_name(format!(".{}.old", archive.username));
+    if vault_dir.exists() {
+        if previous.exists() {
+            fs::remove_dir_all(&previous)
+                .map_err(|e| format!("Failed to clear {:?}: {:?}", previous, e))?;
+        }
+        fs::rename(&vault_dir, &previous)
+            .map_err(|e| format!("Failed to move {:?}: {:?}", vault_dir, e))?;
+    }
+    if let Err(e) = fs::rename(&staging, &vault_dir) {
+        let _ = fs::rename(&previous, &vault_dir);
+        return Err(format!("Failed to restore {:?}: {:?}", vault_dir, e));
+    }
+    for (path, _) in &files {
+        fs::rename(path.with_extension("tmp"), path)
+            .map_err(|e| format!("Failed to write {:?}: {:?}", path, e))?;
+    }
+    let _ = fs::remove_dir_all(&previous);
+
+    if restores_config {
+        config::reload()?;
+    }
+
+    Ok(archive.username)
+}
+
+// Only the files export writes may be restored, so a crafted backup cannot
+// write anywhere else in the working or config directory
+fn destination(file: &BackupFile, username: &str) -> Result<Destination, String> {
+    let relative = Path::new(&file.path);
+    let normal = relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+    let vault_prefix = Path::new("users").join(username);
+
+    match file.root {
+        BackupRoot::Account if normal && relative == Path::new(ACCOUNT_FILE) => {
+            return Ok(Destination::File(file.root, relative.to_path_buf()));
+        }
+        BackupRoot::Config
+            if normal
+                && (relative == Path::new(SECRET_KEY_FILE)
+                    || relative == Path::new(config::FILE_NAME)) =>
+        {
+            return Ok(Destination::File(file.root, relative.to_path_buf()));
+        }
+        BackupRoot::Config if normal => {
+            if let Ok(inner) = relative.strip_prefix(&vault_prefix) {
+                if inner.components().next().is_some() {
+                    return Ok(Destination::Vault(inner.to_path_buf()));
+                }
+            }
+        }
+        _ => {}
+    }
+    Err(format!("Backup contains an unexpected file: {}", file.path))
+}
+
+// Write vault records into `staging` and every other file beside its target
+fn stage(
+    staging: &Path,
+    vault_files: &[(PathBuf, Vec<u8>)],
+    files: &[(PathBuf, Vec<u8>)],
+) -> Result<(), String> {
+    if staging.exists() {
+        fs::remove_dir_all(staging)
+            .map_err(|e| format!("Failed to clear {:?}: {:?}", staging, e))?;
+    }
+    fs::create_dir_all(staging).map_err(|e| format!("Failed to create {:?}: {:?}", staging, e))?;
+
+    let vault_targets = vault_files
+        .iter()
+        .map(|(relative, contents)| (staging.join(relative), contents));
+    let file_targets = files
+        .iter()
+        .map(|(path, contents)| (path.with_extension("tmp"), contents));
+    for (path, contents) in vault_targets.chain(file_targets) {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {:?}: {:?}", parent, e))?;
+        }
+        fs::write(&path, contents).map_err(|e| format!("Failed to write {:?}: {:?}", path, e))?;
+    }
+    Ok(())
+}
+
+// Refuse settings this version cannot load before they replace working ones
+fn check_config(contents: &[u8]) -> Result<(), String> {
+    let contents =
+        std::str::from_utf8(contents).map_err(|_| "Backup settings are corrupted".to_string())?;
+    let config: Config =
+        toml::from_str(contents).map_err(|e| format!("Backup settings are invalid: {}", e))?;
+    config.validate()
+}
+
+// Define the state of the backup export page
+#[derive(Default)]
+pub struct BackupPage {
+    passphrase: String,
+    confirm: String,
+    status: Option<Result<String, String>>,
+    passphrase_input: text_input::State,
+    confirm_input: text_input::State,
+    export_button: button::State,
+    back_button: button::State,
+}
+
+impl BackupPage {
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn passphrase_changed(&mut self, value: String) {
+        self.passphrase = value;
+    }
+
+    pub fn confirm_changed(&mut self, value: String) {
+        self.confirm = value;
+    }
+
+    pub fn export(&mut self, username: &str) {
+        if self.passphrase.is_empty() {
+            self.status = Some(Err("Enter a backup passphrase".to_string()));
+            return;
+        }
+        if self.passphrase != self.confirm {
+            self.status = Some(Err("Passphrases do not match".to_string()));
+            return;
+        }
+
+        let destination = match session::output_dialog()
+            .set_file_name("lockbox-backup.age")
+            .save_file()
+        {
+            Some(destination) => destination,
+            None => return,
+        };
+        session::remember_output(&destination);
+
+        self.status = Some(
+            export(username, &self.passphrase, &destination)
+                .map(|count| format!("Backed up {} files to {}", count, destination.display())),
+        );
+        self.passphrase.clear();
+        self.confirm.clear();
+    }
+
+    pub fn view(&mut self) -> Element<'_, crate::Message> {
+        let logo = Container::new(
+            Image::new("images/logo.png")
+                .width(Length::Units(40))
+                .height(Length::Units(40)),
+        )
+        .padding(10)
+        .align_x(alignment::Horizontal::Center);
+
+        let form = Column::new()
+            .spacing(10)
+            .align_items(Alignment::Center)
+            .push(Text::new("Export Backup").size(24))
+            .push(
+                Text::new(
+                    "Bundles your account, keys and vault into one file. \
+                     The backup passphrase is separate from your login password.",
+                )
+                .size(14)
+                .width(Length::Units(320)),
+            )
+            .push(
+                TextInput::new(
+                    &mut self.passphrase_input,
+                    "backup passphrase",
+                    &self.passphrase,
+                    crate::Message::BackupPassphraseChanged,
+                )
+                .padding(10)
+                .size(16)
+                .width(Length::Units(300))
+                .password(),
+            )
+            .push(
+                TextInput::new(
+                    &mut self.confirm_input,
+                    "confirm passphrase",
+                    &self.confirm,
+                    crate::Message::BackupConfirmChanged,
+                )
+                .padding(10)
+                .size(16)
+                .width(Length::Units(300))
+                .password(),
+            )
+            .push(
+                Button::new(&mut self.export_button, Text::new("Export").size(20))
+                    .style(GreenButton)
+                    .on_press(crate::Message::ExportBackup),
+            );
+
+        let mut content = Column::new()
+            .spacing(20)
+            .align_items(Alignment::Center)
+            .push(Container::new(form).style(BlueBorderContainer).padding(20));
+
+        if let Some(status) = &self.status {
+            content = content.push(status_text(status));
+        }
+
+        content = content.push(
+            Button::new(&mut self.back_button, Text::new("Back").size(16))
+                .style(BlueButton)
+                .on_press(crate::Message::SwitchToStorePage),
+        );
+
+        let container = Container::new(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y();
+
+        Column::new().push(logo).push(container).into()
+    }
+}
+
+// Define the state of the restore form on the login screen
+#[derive(Default)]
+pub struct RestoreForm {
+    source: Option<PathBuf>,
+    passphrase: String,
+    replace: bool,
+    status: Option<Result<String, String>>,
+    passphrase_input: text_input::State,
+    choose_button: button::State,
+    restore_button: button::State,
+    cancel_button: button::State,
+}
+
+impl RestoreForm {
+    pub fn choose_source(&mut self) {
+        if let Some(path) = session::open_dialog()
+            .add_filter("lockbox backup", &["age"])
+            .pick_file()
+        {
+            session::remember_open(&path);
+            self.source = Some(path);
+        }
+    }
+
+    pub fn passphrase_changed(&mut self, value: String) {
+        self.passphrase = value;
+    }
+
+    pub fn replace_toggled(&mut self, replace: bool) {
+        self.replace = replace;
+    }
+
+    // Restore the chosen archive, returning the restored username on success
+    pub fn restore(&mut self) -> Option<String> {
+        let source = self.source.as_ref()?;
+        let result = restore(source, &self.passphrase, self.replace);
+        self.passphrase.clear();
+        self.status = Some(
+            result
+                .as_ref()
+                .map(|username| format!("Restored profile for {}. You can now log in.", username))
+                .map_err(|e| e.clone()),
+        );
+        result.ok()
+    }
+
+    pub fn view(&mut self) -> Element<'_, crate::Message> {
+        let source_label = match &self.source {
+            Some(source) => source
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            None => "Choose Backup".to_string(),
+        };
+
+        let mut content = Column::new()
+            .spacing(20)
+            .align_items(Alignment::Center)
+            .push(Text::new("Restore Backup").size(24))
+            .push(
+                Button::new(&mut self.choose_button, Text::new(source_label).size(16))
+                    .style(BlueButton)
+                    .on_press(crate::Message::ChooseRestoreSource),
+            )
+            .push(
+                TextInput::new(
+                    &mut self.passphrase_input,
+                    "backup passphrase",
+                    &self.passphrase,
+                    crate::Message::RestorePassphraseChanged,
+                )
+                .padding(10)
+                .size(20)
+                .width(Length::Units(200))
+                .password(),
+            )
+            .push(
+                Checkbox::new(
+                    self.replace,
+                    "Replace existing profile",
+                    crate::Message::ToggleRestoreReplace,
+                )
+                .size(16),
+            )
+            .push(
+                Button::new(&mut self.restore_button, Text::new("restore"))
+                    .on_press(crate::Message::RestoreBackup),
+            );
+
+        if let Some(status) = &self.status {
+            content = content.push(status_text(status));
+        }
+
+        content
+            .push(
+                Button::new(&mut self.cancel_button, Text::new("back to login"))
+                    .on_press(crate::Message::ToggleRestoreForm),
+            )
+            .into()
+    }
+}
+
+fn status_text(status: &Result<String, String>) -> Text {
+    match status {
+        Ok(message) => Text::new(message)
+            .size(16)
+            .color(Color::from_rgb(0.3, 0.6, 0.4)),
+        Err(error) => Text::new(error)
+            .size(16)
+            .color(Color::from_rgb(0.8, 0.2, 0.2)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(root: BackupRoot, path: &str) -> BackupFile {
+        BackupFile {
+            root,
+            path: path.to_string(),
+            contents: String::new(),
+        }
+    }
+
+    #[test]
+    fn accepts_the_files_export_writes() {
+        let account = file(BackupRoot::Account, ACCOUNT_FILE);
+        assert_eq!(
+            destination(&account, "alice"),
+            Ok(Destination::File(
+                BackupRoot::Account,
+                PathBuf::from(ACCOUNT_FILE)
+            ))
+        );
+        let key = file(BackupRoot::Config, SECRET_KEY_FILE);
+        assert_eq!(
+            destination(&key, "alice"),
+            Ok(Destination::File(
+                BackupRoot::Config,
+                PathBuf::from(SECRET_KEY_FILE)
+            ))
+        );
+        let record = file(BackupRoot::Config, "users/alice/records/notes.age");
+        assert_eq!(
+            destination(&record, "alice"),
+            Ok(Destination::Vault(PathBuf::from("records/notes.age")))
+        );
+    }
+
+    #[test]
+    fn rejects_files_outside_the_profile() {
+        let rejected = [
+            file(BackupRoot::Config, "users/bob/identity.age"),
+            file(BackupRoot::Config, "users/alice/../bob/identity.age"),
+            file(BackupRoot::Config, "users/alice"),
+            file(BackupRoot::Config, ACCOUNT_FILE),
+            file(BackupRoot::Account, SECRET_KEY_FILE),
+            file(BackupRoot::Account, "src/main.rs"),
+            file(BackupRoot::Account, "/etc/passwd"),
+        ];
+        for entry in &rejected {
+            assert!(destination(entry, "alice").is_err(), "{}", entry.path);
+        }
+    }
+}
@@ -0,0 +1,466 @@
+// src/cipher.rs
+
+use crate::config;
+use aes_gcm::Aes256Gcm;
+use age::armor::{ArmoredReader, ArmoredWriter, Format};
+use age::secrecy::SecretString;
+use age::{x25519, Decryptor, Encryptor};
+use chacha20poly1305::aead::{Aead, NewAead, Payload};
+use chacha20poly1305::XChaCha20Poly1305;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use std::fmt;
+use std::io::{Read, Write};
+
+// Magic bytes and format version at the start of every AEAD file
+const AEAD_MAGIC: &[u8] = b"LBXAEAD\x01";
+
+const SALT_LEN: usize = 16;
+
+// Encrypts and decrypts whole streams, writing output that identifies its backend
+pub trait Cipher {
+    fn encrypt(&self, input: &mut dyn Read, output: &mut dyn Write) -> Result<(), String>;
+    fn decrypt(&self, input: &mut dyn Read, output: &mut dyn Write) -> Result<(), String>;
+
+    fn encrypt_bytes(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        let mut output = Vec::new();
+        self.encrypt(&mut &data[..], &mut output)?;
+        Ok(output)
+    }
+
+    fn decrypt_bytes(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        let mut output = Vec::new();
+        self.decrypt(&mut &data[..], &mut output)?;
+        Ok(output)
+    }
+}
+
+// Backends StorePage can encrypt files with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Backend {
+    #[default]
+    AgePassphrase,
+    XChaCha20Poly1305,
+    Aes256Gcm,
+}
+
+impl Backend {
+    pub const ALL: [Backend; 3] = [
+        Backend::AgePassphrase,
+        Backend::XChaCha20Poly1305,
+        Backend::Aes256Gcm,
+    ];
+
+    // Cipher for this backend keyed by a passphrase
+    pub fn with_passphrase(self, passphrase: &str) -> Box<dyn Cipher> {
+        match self {
+            Backend::AgePassphrase => Box::new(AgePassphrase::new(passphrase, Format::Binary)),
+            Backend::XChaCha20Poly1305 => Box::new(AeadPassphrase::new(
+                AeadAlgorithm::XChaCha20Poly1305,
+                passphrase,
+            )),
+            Backend::Aes256Gcm => {
+                Box::new(AeadPassphrase::new(AeadAlgorithm::Aes256Gcm, passphrase))
+            }
+        }
+    }
+
+    // Work out which backend produced some ciphertext from its header
+    pub fn detect(data: &[u8]) -> Option<Backend> {
+        if data.starts_with(AEAD_MAGIC) {
+            return match data.get(AEAD_MAGIC.len()) {
+                Some(&id) if id == AeadAlgorithm::XChaCha20Poly1305 as u8 => {
+                    Some(Backend::XChaCha20Poly1305)
+                }
+                Some(&id) if id == AeadAlgorithm::Aes256Gcm as u8 => Some(Backend::Aes256Gcm),
+                _ => None,
+            };
+        }
+        if data.starts_with(b"age-encryption.org/v1")
+            || data.starts_with(b"-----BEGIN AGE ENCRYPTED FILE-----")
+        {
+            return Some(Backend::AgePassphrase);
+        }
+        None
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Backend::AgePassphrase => "age (scrypt)",
+            Backend::XChaCha20Poly1305 => "XChaCha20-Poly1305",
+            Backend::Aes256Gcm => "AES-256-GCM",
+        })
+    }
+}
+
+// age with a scrypt passphrase, optionally ASCII armored
+pub struct AgePassphrase {
+    passphrase: String,
+    armored: bool,
+}
+
+impl AgePassphrase {
+    pub fn new(passphrase: &str, format: Format) -> Self {
+        Self {
+            passphrase: passphrase.to_string(),
+            armored: matches!(format, Format::AsciiArmor),
+        }
+    }
+}
+
+impl Cipher for AgePassphrase {
+    fn encrypt(&self, input: &mut dyn Read, output: &mut dyn Write) -> Result<(), String> {
+        let encryptor = Encryptor::with_user_passphrase(SecretString::new(self.passphrase.clone()));
+        let format = if self.armored {
+            Format::AsciiArmor
+        } else {
+            Format::Binary
+        };
+        let armored = ArmoredWriter::wrap_output(output, format)
+            .map_err(|e| format!("Failed to create encryptor: {:?}", e))?;
+        let mut writer = encryptor
+            .wrap_output(armored)
+            .map_err(|e| format!("Failed to create encryptor: {:?}", e))?;
+        std::io::copy(input, &mut writer)
+            .map_err(|e| format!("Failed to encrypt data: {:?}", e))?;
+        writer
+            .finish()
+            .and_then(|armored| armored.finish())
+            .map(|_| ())
+            .map_err(|e| format!("Failed to finalize encryption: {:?}", e))
+    }
+
+    fn decrypt(&self, input: &mut dyn Read, output: &mut dyn Write) -> Result<(), String> {
+        let decryptor = match Decryptor::new(ArmoredReader::new(input)) {
+            Ok(Decryptor::Passphrase(decryptor)) => decryptor,
+            Ok(_) => return Err("Unsupported decryptor".to_string()),
+            Err(e) => return Err(format!("Failed to create decryptor: {:?}", e)),
+        };
+        let mut reader = decryptor
+            .decrypt(&SecretString::new(self.passphrase.clone()), None)
+            .map_err(|e| format!("Failed to decrypt data: {:?}", e))?;
+        std::io::copy(&mut reader, output)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to decrypt data: {:?}", e))
+    }
+}
+
+// age to x25519 recipients, decrypting with matching identities
+pub struct AgeRecipients {
+    recipients: Vec<x25519::Recipient>,
+    identities: Vec<x25519::Identity>,
+}
+
+impl AgeRecipients {
+    pub fn new(recipients: Vec<x25519::Recipient>, identities: Vec<x25519::Identity>) -> Self {
+        Self {
+            recipients,
+            identities,
+        }
+    }
+}
+
+impl Cipher for AgeRecipients {
+    fn encrypt(&self, input: &mut dyn Read, output: &mut dyn Write) -> Result<(), String> {
+        let recipients: Vec<Box<dyn age::Recipient>> = self
+            .recipients
+            .iter()
+            .map(|recipient| Box::new(recipient.clone()) as Box<dyn age::Recipient>)
+            .collect();
+        let mut writer = Encryptor::with_recipients(recipients)
+            .wrap_output(output)
+            .map_err(|e| format!("Failed to create encryptor: {:?}", e))?;
+        std::io::copy(input, &mut writer)
+            .map_err(|e| format!("Failed to encrypt data: {:?}", e))?;
+        writer
+            .finish()
+            .map(|_| ())
+            .map_err(|e| format!("Failed to finalize encryption: {:?}", e))
+    }
+
+    fn decrypt(&self, input: &mut dyn Read, output: &mut dyn Write) -> Result<(), String> {
+        let decryptor = match Decryptor::new(ArmoredReader::new(input)) {
+            Ok(Decryptor::Recipients(decryptor)) => decryptor,
+            Ok(_) => return Err("Unsupported decryptor".to_string()),
+            Err(e) => return Err(format!("Failed to create decryptor: {:?}", e)),
+        };
+        let mut reader = decryptor
+            .decrypt(
+                self.identities
+                    .iter()
+                    .map(|identity| identity as &dyn age::Identity),
+            )
+            .map_err(|e| format!("Failed to decrypt data: {:?}", e))?;
+        std::io::copy(&mut reader, output)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to decrypt data: {:?}", e))
+    }
+}
+
+// AEAD algorithms, numbered as stored in the file header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AeadAlgorithm {
+    XChaCha20Poly1305 = 1,
+    Aes256Gcm = 2,
+}
+
+impl AeadAlgorithm {
+    fn from_id(id: u8) -> Result<Self, String> {
+        match id {
+            1 => Ok(AeadAlgorithm::XChaCha20Poly1305),
+            2 => Ok(AeadAlgorithm::Aes256Gcm),
+            _ => Err(format!("Unknown AEAD algorithm {}", id)),
+        }
+    }
+
+    fn nonce_len(self) -> usize {
+        match self {
+            AeadAlgorithm::XChaCha20Poly1305 => 24,
+            AeadAlgorithm::Aes256Gcm => 12,
+        }
+    }
+
+    fn seal(self, key: &[u8; 32], nonce: &[u8], payload: Payload) -> Result<Vec<u8>, String> {
+        match self {
+            AeadAlgorithm::XChaCha20Poly1305 => {
+                XChaCha20Poly1305::new(key.into()).encrypt(nonce.into(), payload)
+            }
+            AeadAlgorithm::Aes256Gcm => Aes256Gcm::new(key.into()).encrypt(nonce.into(), payload),
+        }
+        .map_err(|_| "Failed to encrypt data".to_string())
+    }
+
+    fn open(self, key: &[u8; 32], nonce: &[u8], payload: Payload) -> Result<Vec<u8>, String> {
+        match self {
+            AeadAlgorithm::XChaCha20Poly1305 => {
+                XChaCha20Poly1305::new(key.into()).decrypt(nonce.into(), payload)
+            }
+            AeadAlgorithm::Aes256Gcm => Aes256Gcm::new(key.into()).decrypt(nonce.into(), payload),
+        }
+        .map_err(|_| "Failed to decrypt data: wrong passphrase or corrupted file".to_string())
+    }
+}
+
+// AEAD keyed by an scrypt-derived passphrase key
+//
+// Header: magic (8) | algorithm (1) | scrypt log_n (1) | salt (16) | nonce (24 or 12),
+// authenticated as associated data ahead of the ciphertext
+pub struct AeadPassphrase {
+    algorithm: AeadAlgorithm,
+    passphrase: String,
+    log_n: u8,
+}
+
+impl AeadPassphrase {
+    // New files use the scrypt work factor from the config
+    pub fn new(algorithm: AeadAlgorithm, passphrase: &str) -> Self {
+        Self {
+            algorithm,
+            passphrase: passphrase.to_string(),
+            log_n: config::current().security.scrypt_log_n,
+        }
+    }
+
+    fn derive_key(&self, log_n: u8, salt: &[u8]) -> Result<[u8; 32], String> {
+        let params = scrypt::Params::new(log_n, 8, 1)
+            .map_err(|e| format!("Invalid key derivation parameters: {}", e))?;
+        let mut key = [0u8; 32];
+        scrypt::scrypt(self.passphrase.as_bytes(), salt, &params, &mut key)
+            .map_err(|e| format!("Failed to derive key: {}", e))?;
+        Ok(key)
+    }
+}
+
+impl Cipher for AeadPassphrase {
+    fn encrypt(&self, input: &mut dyn Read, output: &mut dyn Write) -> Result<(), String> {
+        let mut plaintext = Vec::new();
+        input
+            .read_to_end(&mut plaintext)
+            .map_err(|e| format!("Failed to read data: {:?}", e))?;
+
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce = vec![0u8; self.algorithm.nonce_len()];
+        OsRng.fill_bytes(&mut salt);
+        OsRng.fill_bytes(&mut nonce);
+
+        let mut header = AEAD_MAGIC.to_vec();
+        header.push(self.algorithm as u8);
+        header.push(self.log_n);
+        header.extend_from_slice(&salt);
+        header.extend_from_slice(&nonce);
+
+        let key = self.derive_key(self.log_n, &salt)?;
+        let ciphertext = self.algorithm.seal(
+            &key,
+            &nonce,
+            Payload {
+                msg: &plaintext,
+                aad: &header,
+            },
+        )?;
+
+        output
+            .write_all(&header)
+            .and_then(|_| output.write_all(&ciphertext))
+            .map_err(|e| format!("Failed to write encrypted data: {:?}", e))
+    }
+
+    fn decrypt(&self, input: &mut dyn Read, output: &mut dyn Write) -> Result<(), String> {
+        let mut data = Vec::new();
+        input
+            .read_to_end(&mut data)
+            .map_err(|e| format!("Failed to read data: {:?}", e))?;
+        if !data.starts_with(AEAD_MAGIC) {
+            return Err("Not a lockbox AEAD file".to_string());
+        }
+
+        let truncated = || "Encrypted file is truncated".to_string();
+        let mut offset = AEAD_MAGIC.len();
+        let algorithm = AeadAlgorithm::from_id(*data.get(offset).ok_or_else(truncated)?)?;
+        let log_n = *data.get(offset + 1).ok_or_else(truncated)?;
+        // A crafted header could otherwise make scrypt take hours or all memory
+        if !config::SCRYPT_LOG_N_RANGE.contains(&log_n) {
+            return Err(format!("Unsupported key derivation work factor {}", log_n));
+        }
+        offset += 2;
+        let salt = data.get(offset..offset + SALT_LEN).ok_or_else(truncated)?;
+        offset += SALT_LEN;
+        let nonce = data
+            .get(offset..offset + algorithm.nonce_len())
+            .ok_or_else(truncated)?;
+        offset += algorithm.nonce_len();
+
+        let key = self.derive_key(log_n, salt)?;
+        let plaintext = algorithm.open(
+            &key,
+            nonce,
+            Payload {
+                msg: &data[offset..],
+                aad: &data[..offset],
+            },
+        )?;
+        output
+            .write_all(&plaintext)
+            .map_err(|e| format!("Failed to write decrypted data: {:?}", e))
+    }
+}
+
+// Cipher able to decrypt data from any passphrase backend, chosen by its header
+pub fn detect_passphrase(data: &[u8], passphrase: &str) -> Result<Box<dyn Cipher>, String> {
+    let backend = Backend::detect(data).ok_or("Unrecognised encrypted file format")?;
+    Ok(backend.with_passphrase(passphrase))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The lowest accepted work factor keeps the tests fast
+    fn cipher(algorithm: AeadAlgorithm, passphrase: &str) -> AeadPassphrase {
+        AeadPassphrase {
+            algorithm,
+            passphrase: passphrase.to_string(),
+            log_n: *config::SCRYPT_LOG_N_RANGE.start(),
+        }
+    }
+
+    fn sealed(algorithm: AeadAlgorithm) -> Vec<u8> {
+        cipher(algorithm, "correct horse")
+            .encrypt_bytes(b"attack at dawn")
+            .unwrap()
+    }
+
+    #[test]
+    fn both_algorithms_round_trip() {
+        for algorithm in [AeadAlgorithm::XChaCha20Poly1305, AeadAlgorithm::Aes256Gcm] {
+            let data = sealed(algorithm);
+            let header_len = AEAD_MAGIC.len() + 2 + SALT_LEN + algorithm.nonce_len();
+            assert_eq!(data[AEAD_MAGIC.len()], algorithm as u8);
+            assert_eq!(
+                data[AEAD_MAGIC.len() + 1],
+                *config::SCRYPT_LOG_N_RANGE.start()
+            );
+            // The tag adds 16 bytes to the plaintext
+            assert_eq!(data.len(), header_len + 14 + 16);
+            assert_eq!(
+                cipher(algorithm, "correct horse")
+                    .decrypt_bytes(&data)
+                    .unwrap(),
+                b"attack at dawn"
+            );
+        }
+    }
+
+    #[test]
+    fn header_identifies_the_backend() {
+        assert_eq!(
+            Backend::detect(&sealed(AeadAlgorithm::XChaCha20Poly1305)),
+            Some(Backend::XChaCha20Poly1305)
+        );
+        assert_eq!(
+            Backend::detect(&sealed(AeadAlgorithm::Aes256Gcm)),
+            Some(Backend::Aes256Gcm)
+        );
+        assert_eq!(Backend::detect(b"LBXAEAD\x01\x09"), None);
+        assert_eq!(
+            Backend::detect(b"age-encryption.org/v1\n"),
+            Some(Backend::AgePassphrase)
+        );
+        assert_eq!(Backend::detect(b"plain text"), None);
+    }
+
+    #[test]
+    fn wrong_passphrase_fails() {
+        let data = sealed(AeadAlgorithm::XChaCha20Poly1305);
+        assert!(cipher(AeadAlgorithm::XChaCha20Poly1305, "wrong")
+            .decrypt_bytes(&data)
+            .is_err());
+    }
+
+    #[test]
+    fn tampered_header_fails_authentication() {
+        let mut data = sealed(AeadAlgorithm::Aes256Gcm);
+        // Flip a salt byte, which is only protected as associated data
+        data[AEAD_MAGIC.len() + 2] ^= 1;
+        assert!(cipher(AeadAlgorithm::Aes256Gcm, "correct horse")
+            .decrypt_bytes(&data)
+            .is_err());
+    }
+
+    #[test]
+    fn malformed_headers_are_rejected() {
+        let decrypt = |data: &[u8]| {
+            cipher(AeadAlgorithm::XChaCha20Poly1305, "correct horse")
+                .decrypt_bytes(data)
+                .unwrap_err()
+        };
+        let data = sealed(AeadAlgorithm::XChaCha20Poly1305);
+
+        assert_eq!(decrypt(b"NOTAEAD\x01"), "Not a lockbox AEAD file");
+        assert_eq!(decrypt(AEAD_MAGIC), "Encrypted file is truncated");
+        assert_eq!(
+            decrypt(&data[..AEAD_MAGIC.len() + 2 + SALT_LEN + 5]),
+            "Encrypted file is truncated"
+        );
+
+        let mut unknown = data.clone();
+        unknown[AEAD_MAGIC.len()] = 9;
+        assert_eq!(decrypt(&unknown), "Unknown AEAD algorithm 9");
+    }
+
+    #[test]
+    fn work_factor_outside_the_range_is_rejected() {
+        for log_n in [0, 9, 23, 63, 255] {
+            let mut data = sealed(AeadAlgorithm::XChaCha20Poly1305);
+            data[AEAD_MAGIC.len() + 1] = log_n;
+            assert_eq!(
+                cipher(AeadAlgorithm::XChaCha20Poly1305, "correct horse")
+                    .decrypt_bytes(&data)
+                    .unwrap_err(),
+                format!("Unsupported key derivation work factor {}", log_n)
+            );
+        }
+    }
+}
@@ -0,0 +1,25 @@
+// src/clipboard.rs
+
+use crate::Message;
+use iced::{clipboard, Command};
+use std::time::Duration;
+
+// How long a copied secret stays on the clipboard
+const CLEAR_AFTER: Duration = Duration::from_secs(30);
+
+// Copy a secret and schedule wiping it; `generation` identifies this copy
+pub fn copy_secret(value: String, generation: u64) -> Command<Message> {
+    Command::batch(vec![
+        clipboard::write(value),
+        Command::perform(tokio::time::sleep(CLEAR_AFTER), move |_| {
+            Message::ClearClipboard(generation)
+        }),
+    ])
+}
+
+// Wipe the clipboard, but only if it still holds the secret we put there
+pub fn clear_secret(secret: String) -> Command<Message> {
+    clipboard::read(move |current| {
+        Message::ClipboardChecked(current.as_deref() == Some(secret.as_str()))
+    })
+}
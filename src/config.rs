@@ -0,0 +1,229 @@
+// src/config.rs
+
+use crate::confirm::Action;
+use crate::theme::ThemeSetting;
+use dirs::config_dir;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+// Configuration every page reads, loaded from disk on first use
+static CURRENT: RwLock<Option<Config>> = RwLock::new(None);
+
+// Accepted scrypt work factors (log2 of the iteration count)
+pub const SCRYPT_LOG_N_RANGE: std::ops::RangeInclusive<u8> = 10..=22;
+
+// Highest zxcvbn score, "Very strong"
+pub const MAX_PASSWORD_SCORE: u8 = 4;
+
+// Longest auto-lock timeout, one day
+const MAX_AUTO_LOCK_MINUTES: u32 = 24 * 60;
+
+// Name of the settings file inside the lockbox config directory
+pub const FILE_NAME: &str = "config.toml";
+
+// Smallest window the pages still fit in
+pub const MIN_WINDOW_SIZE: (u32, u32) = (640, 480);
+
+// Preferences saved to config.toml in the user's config directory
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub theme: ThemeSetting,
+    pub output: OutputConfig,
+    pub security: SecurityConfig,
+    pub window: WindowConfig,
+    pub confirm: ConfirmConfig,
+}
+
+// Where encrypted and decrypted files are written
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutputConfig {
+    // Folder results go to, next to the original when unset
+    pub directory: Option<PathBuf>,
+    // Write results as new files instead of replacing the originals
+    pub keep_originals: bool,
+    // ASCII armor files encrypted with age
+    pub armor: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SecurityConfig {
+    // scrypt cost for the AEAD backends, age picks its own
+    pub scrypt_log_n: u8,
+    // Minutes without input before the vault locks, 0 to never lock
+    pub auto_lock_minutes: u32,
+    // zxcvbn score from 0 to 4 a new password needs
+    pub min_password_score: u8,
+}
+
+impl Default for SecurityConfig {
+    fn default() -> Self {
+        Self {
+            scrypt_log_n: 15,
+            auto_lock_minutes: 0,
+            min_password_score: 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowConfig {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            width: 800,
+            height: 600,
+        }
+    }
+}
+
+// Which confirmations are still shown, saved in config.toml
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConfirmConfig {
+    pub overwrite: bool,
+    pub double_encrypt: bool,
+    pub shred: bool,
+    pub delete_account: bool,
+    pub discard_changes: bool,
+}
+
+impl Default for ConfirmConfig {
+    fn default() -> Self {
+        Self {
+            overwrite: true,
+            double_encrypt: true,
+            shred: true,
+            delete_account: true,
+            discard_changes: true,
+        }
+    }
+}
+
+impl ConfirmConfig {
+    pub fn asks(&self, action: Action) -> bool {
+        match action {
+            Action::Overwrite => self.overwrite,
+            Action::DoubleEncrypt => self.double_encrypt,
+            Action::Shred => self.shred,
+            Action::DeleteAccount => self.delete_account,
+            Action::DiscardChanges => self.discard_changes,
+        }
+    }
+
+    pub fn set(&mut self, action: Action, ask: bool) {
+        match action {
+            Action::Overwrite => self.overwrite = ask,
+            Action::DoubleEncrypt => self.double_encrypt = ask,
+            Action::Shred => self.shred = ask,
+            Action::DeleteAccount => self.delete_account = ask,
+            Action::DiscardChanges => self.discard_changes = ask,
+        }
+    }
+}
+
+impl Config {
+    // Reject values that would break encryption or the window
+    pub fn validate(&self) -> Result<(), String> {
+        if !SCRYPT_LOG_N_RANGE.contains(&self.security.scrypt_log_n) {
+            return Err(format!(
+                "The scrypt work factor must be between {} and {}",
+                SCRYPT_LOG_N_RANGE.start(),
+                SCRYPT_LOG_N_RANGE.end()
+            ));
+        }
+        if self.security.auto_lock_minutes > MAX_AUTO_LOCK_MINUTES {
+            return Err(format!(
+                "The auto-lock timeout must be at most {} minutes",
+                MAX_AUTO_LOCK_MINUTES
+            ));
+        }
+        if self.security.min_password_score > MAX_PASSWORD_SCORE {
+            return Err(format!(
+                "The minimum password score must be between 0 and {}",
+                MAX_PASSWORD_SCORE
+            ));
+        }
+        if self.window.width < MIN_WINDOW_SIZE.0 || self.window.height < MIN_WINDOW_SIZE.1 {
+            return Err(format!(
+                "The window must be at least {}x{}",
+                MIN_WINDOW_SIZE.0, MIN_WINDOW_SIZE.1
+            ));
+        }
+        Ok(())
+    }
+
+    // Save after validating, also checking the output directory exists right now
+    pub fn save(&self) -> Result<(), String> {
+        self.validate()?;
+        if let Some(directory) = &self.output.directory {
+            if !directory.is_dir() {
+                return Err(format!(
+                    "Output directory {} does not exist",
+                    directory.display()
+                ));
+            }
+        }
+        let path = path()?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create config directory: {:?}", e))?;
+        }
+        let toml = toml::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize config: {:?}", e))?;
+        fs::write(path, toml).map_err(|e| format!("Failed to save config: {:?}", e))?;
+        set_current(self.clone());
+        Ok(())
+    }
+}
+
+// Settings are needed before login, so they live outside the encrypted vault
+fn path() -> Result<PathBuf, String> {
+    let config_dir = config_dir().ok_or("Failed to get config directory")?;
+    Ok(config_dir.join("lockbox").join(FILE_NAME))
+}
+
+// Read config.toml, using the defaults when it is missing
+pub fn load() -> Result<Config, String> {
+    let path = path()?;
+    if !path.exists() {
+        return Ok(Config::default());
+    }
+    let contents =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read config: {:?}", e))?;
+    let config: Config =
+        toml::from_str(&contents).map_err(|e| format!("Failed to parse config: {}", e))?;
+    config.validate()?;
+    Ok(config)
+}
+
+// Active configuration, falling back to the defaults when the file is unusable
+pub fn current() -> Config {
+    if let Some(config) = CURRENT.read().ok().and_then(|config| config.clone()) {
+        return config;
+    }
+    let config = load().unwrap_or_default();
+    set_current(config.clone());
+    config
+}
+
+// Pick up a config.toml replaced on disk, such as by a restored backup
+pub fn reload() -> Result<(), String> {
+    set_current(load()?);
+    Ok(())
+}
+
+fn set_current(config: Config) {
+    if let Ok(mut current) = CURRENT.write() {
+        *current = Some(config);
+    }
+}
@@ -0,0 +1,409 @@
+// src/confirm.rs
+
+use crate::config;
+use crate::store::{BlueBorderContainer, BlueButton, OrangeButton};
+use iced::{button, Alignment, Button, Checkbox, Column, Container, Element, Length, Row, Text};
+use std::path::{Path, PathBuf};
+
+// Files named in a confirmation before the rest are counted
+const LISTED_FILES: usize = 5;
+
+// Destructive actions that ask first, each with its own "don't ask again"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Overwrite,
+    DoubleEncrypt,
+    Shred,
+    DeleteAccount,
+    DiscardChanges,
+}
+
+impl Action {
+    pub const ALL: [Action; 5] = [
+        Action::Overwrite,
+        Action::DoubleEncrypt,
+        Action::Shred,
+        Action::DeleteAccount,
+        Action::DiscardChanges,
+    ];
+
+    fn title(self) -> &'static str {
+        match self {
+            Action::Overwrite => "Replace existing files?",
+            Action::DoubleEncrypt => "Already encrypted",
+            Action::Shred => "Shred file",
+            Action::DeleteAccount => "Delete account",
+            Action::DiscardChanges => "Unsaved changes",
+        }
+    }
+
+    fn confirm_label(self) -> &'static str {
+        match self {
+            Action::Overwrite => "Replace",
+            Action::DoubleEncrypt => "Encrypt Again",
+            Action::Shred => "Shred",
+            Action::DeleteAccount => "Delete Account",
+            Action::DiscardChanges => "Discard",
+        }
+    }
+
+    // Label for the settings checkbox turning the question back on
+    pub fn setting_label(self) -> &'static str {
+        match self {
+            Action::Overwrite => "Ask before replacing existing output files",
+            Action::DoubleEncrypt => "Ask before encrypting files that are already encrypted",
+            Action::Shred => "Ask before shredding a file",
+            Action::DeleteAccount => "Ask before deleting the account",
+            Action::DiscardChanges => "Ask before discarding unsaved env file changes",
+        }
+    }
+}
+
+// Checks the user already agreed to skip for a batch of files
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Allowed {
+    pub overwrite: bool,
+    pub double_encrypt: bool,
+}
+
+impl Allowed {
+    // Questions the user turned off count as answered
+    pub fn with_settings(self) -> Self {
+        let asks = config::current().confirm;
+        Self {
+            overwrite: self.overwrite || !asks.overwrite,
+            double_encrypt: self.double_encrypt || !asks.double_encrypt,
+        }
+    }
+}
+
+// What replaces an env file with unsaved changes once they are discarded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Discard {
+    NewEnvFile,
+    OpenEnvFile,
+    LeaveEnvPage,
+    Lock,
+    Quit,
+}
+
+impl Discard {
+    pub fn message(self) -> String {
+        let next = match self {
+            Discard::NewEnvFile => "start a new file",
+            Discard::OpenEnvFile => "open another file",
+            Discard::LeaveEnvPage => "leave the page",
+            Discard::Lock => "lock",
+            Discard::Quit => "quit",
+        };
+        format!(
+            "The env file has changes that are not saved. Discard them and {}?",
+            next
+        )
+    }
+}
+
+// Work held back until the user answers
+#[derive(Debug, Clone)]
+pub enum Pending {
+    Encrypt(Vec<PathBuf>, Allowed),
+    Decrypt(Vec<PathBuf>, Allowed),
+    Shred(PathBuf, u8),
+    DeleteAccount,
+    Discard(Discard),
+}
+
+// A question shown over the page, blocking everything else until answered
+pub struct Confirmation {
+    action: Action,
+    message: String,
+    pending: Pending,
+    dont_ask: bool,
+    confirm_button: button::State,
+    cancel_button: button::State,
+}
+
+impl Confirmation {
+    pub fn new(action: Action, message: String, pending: Pending) -> Self {
+        Self {
+            action,
+            message,
+            pending,
+            dont_ask: false,
+            confirm_button: button::State::new(),
+            cancel_button: button::State::new(),
+        }
+    }
+
+    // Ask about files held back from an encrypt or decrypt
+    pub fn for_files(action: Action, pending: Pending) -> Self {
+        Self::new(action, describe(action, &pending), pending)
+    }
+
+    pub fn action(&self) -> Action {
+        self.action
+    }
+
+    pub fn dont_ask(&self) -> bool {
+        self.dont_ask
+    }
+
+    pub fn dont_ask_toggled(&mut self, dont_ask: bool) {
+        self.dont_ask = dont_ask;
+    }
+
+    // Files from a later drop join the question already asked about them
+    pub fn merge(&mut self, other: &Confirmation) -> bool {
+        if self.action != other.action {
+            return false;
+        }
+        match (&mut self.pending, &other.pending) {
+            (Pending::Encrypt(files, allowed), Pending::Encrypt(more, other))
+            | (Pending::Decrypt(files, allowed), Pending::Decrypt(more, other))
+                if allowed == other =>
+            {
+                files.extend(more.iter().cloned());
+                self.message = describe(self.action, &self.pending);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // The held back work, now allowed past this check
+    pub fn confirm(self) -> Pending {
+        let allow = |mut allowed: Allowed| {
+            match self.action {
+                Action::Overwrite => allowed.overwrite = true,
+                Action::DoubleEncrypt => allowed.double_encrypt = true,
+                Action::Shred | Action::DeleteAccount | Action::DiscardChanges => {}
+            }
+            allowed
+        };
+        match self.pending {
+            Pending::Encrypt(files, allowed) => Pending::Encrypt(files, allow(allowed)),
+            Pending::Decrypt(files, allowed) => Pending::Decrypt(files, allow(allowed)),
+            pending => pending,
+        }
+    }
+
+    pub fn view(&mut self) -> Element<'_, crate::Message> {
+        let layout = Column::new()
+            .spacing(15)
+            .width(Length::Units(450))
+            .push(Text::new(self.action.title()).size(24))
+            .push(Text::new(&self.message).size(16))
+            .push(
+                Checkbox::new(
+                    self.dont_ask,
+                    "Don't ask again",
+                    crate::Message::ToggleDontAsk,
+                )
+                .size(16)
+                .text_size(16),
+            )
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .push(
+                        Button::new(
+                            &mut self.confirm_button,
+                            Text::new(self.action.confirm_label()).size(16),
+                        )
+                        .style(OrangeButton)
+                        .on_press(crate::Message::ConfirmAction),
+                    )
+                    .push(
+                        Button::new(&mut self.cancel_button, Text::new("Cancel").size(16))
+                            .style(BlueButton)
+                            .on_press(crate::Message::CancelAction),
+                    ),
+            );
+
+        Container::new(layout)
+            .style(BlueBorderContainer)
+            .padding(20)
+            .into()
+    }
+}
+
+// Question for files held back from an encrypt or decrypt
+fn describe(action: Action, pending: &Pending) -> String {
+    let (files, encrypting) = match pending {
+        Pending::Encrypt(files, _) => (files, true),
+        Pending::Decrypt(files, _) => (files, false),
+        _ => return String::new(),
+    };
+    let one = files.len() == 1;
+    match action {
+        Action::DoubleEncrypt => format!(
+            "{} {} already encrypted. Encrypting again means decrypting twice to read {}.",
+            list(files),
+            if one { "is" } else { "are" },
+            if one { "it" } else { "them" }
+        ),
+        _ => format!(
+            "{} {} would replace {} that already {}.",
+            if encrypting {
+                "Encrypting"
+            } else {
+                "Decrypting"
+            },
+            list(files),
+            if one { "a file" } else { "files" },
+            if one { "exists" } else { "exist" }
+        ),
+    }
+}
+
+// File names joined for a message, long lists cut short
+fn list(files: &[PathBuf]) -> String {
+    let mut names: Vec<String> = files
+        .iter()
+        .take(LISTED_FILES)
+        .map(|path| name(path))
+        .collect();
+    if files.len() > LISTED_FILES {
+        names.push(format!("{} more", files.len() - LISTED_FILES));
+    }
+    match names.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} and {}", rest.join(", "), last),
+        _ => names.concat(),
+    }
+}
+
+fn name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(names: &[&str]) -> Vec<PathBuf> {
+        names
+            .iter()
+            .map(|name| PathBuf::from("/tmp").join(name))
+            .collect()
+    }
+
+    fn files(pending: &Pending) -> Vec<PathBuf> {
+        match pending {
+            Pending::Encrypt(files, _) | Pending::Decrypt(files, _) => files.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    #[test]
+    fn later_drops_merge_into_the_same_question() {
+        let mut first = Confirmation::for_files(
+            Action::Overwrite,
+            Pending::Encrypt(paths(&["a"]), Allowed::default()),
+        );
+        let second = Confirmation::for_files(
+            Action::Overwrite,
+            Pending::Encrypt(paths(&["b"]), Allowed::default()),
+        );
+        assert!(first.merge(&second));
+        assert_eq!(
+            first.message,
+            "Encrypting a and b would replace files that already exist."
+        );
+        assert_eq!(files(&first.pending), paths(&["a", "b"]));
+    }
+
+    #[test]
+    fn different_questions_stay_apart() {
+        let mut overwrite = Confirmation::for_files(
+            Action::Overwrite,
+            Pending::Encrypt(paths(&["a"]), Allowed::default()),
+        );
+        let double = Confirmation::for_files(
+            Action::DoubleEncrypt,
+            Pending::Encrypt(paths(&["b"]), Allowed::default()),
+        );
+        let decrypt = Confirmation::for_files(
+            Action::Overwrite,
+            Pending::Decrypt(paths(&["c"]), Allowed::default()),
+        );
+        let allowed = Allowed {
+            double_encrypt: true,
+            ..Allowed::default()
+        };
+        let already_allowed =
+            Confirmation::for_files(Action::Overwrite, Pending::Encrypt(paths(&["d"]), allowed));
+        assert!(!overwrite.merge(&double));
+        assert!(!overwrite.merge(&decrypt));
+        assert!(!overwrite.merge(&already_allowed));
+        assert_eq!(files(&overwrite.pending), paths(&["a"]));
+    }
+
+    #[test]
+    fn confirming_allows_only_the_answered_check() {
+        let confirmation = Confirmation::for_files(
+            Action::Overwrite,
+            Pending::Decrypt(paths(&["a"]), Allowed::default()),
+        );
+        match confirmation.confirm() {
+            Pending::Decrypt(files, allowed) => {
+                assert_eq!(files, paths(&["a"]));
+                assert!(allowed.overwrite);
+                assert!(!allowed.double_encrypt);
+            }
+            pending => panic!("unexpected {:?}", pending),
+        }
+
+        let confirmation = Confirmation::for_files(
+            Action::DoubleEncrypt,
+            Pending::Encrypt(paths(&["a"]), Allowed::default()),
+        );
+        match confirmation.confirm() {
+            Pending::Encrypt(_, allowed) => {
+                assert!(allowed.double_encrypt);
+                assert!(!allowed.overwrite);
+            }
+            pending => panic!("unexpected {:?}", pending),
+        }
+
+        let shred = Confirmation::new(
+            Action::Shred,
+            String::new(),
+            Pending::Shred(PathBuf::from("a"), 3),
+        );
+        assert!(matches!(shred.confirm(), Pending::Shred(_, 3)));
+    }
+
+    #[test]
+    fn describe_pluralizes() {
+        let one = Pending::Encrypt(paths(&["a"]), Allowed::default());
+        let two = Pending::Decrypt(paths(&["a", "b"]), Allowed::default());
+        assert_eq!(
+            describe(Action::Overwrite, &one),
+            "Encrypting a would replace a file that already exists."
+        );
+        assert_eq!(
+            describe(Action::Overwrite, &two),
+            "Decrypting a and b would replace files that already exist."
+        );
+        assert_eq!(
+            describe(Action::DoubleEncrypt, &one),
+            "a is already encrypted. Encrypting again means decrypting twice to read it."
+        );
+        assert_eq!(
+            describe(Action::DoubleEncrypt, &two),
+            "a and b are already encrypted. Encrypting again means decrypting twice to read them."
+        );
+        assert_eq!(describe(Action::Shred, &Pending::DeleteAccount), "");
+    }
+
+    #[test]
+    fn long_lists_are_cut_short() {
+        let names = ["a", "b", "c", "d", "e", "f", "g"];
+        assert_eq!(list(&paths(&names)), "a, b, c, d, e and 2 more");
+        assert_eq!(list(&paths(&names[..3])), "a, b and c");
+        assert_eq!(list(&[]), "");
+    }
+}
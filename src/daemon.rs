@@ -0,0 +1,330 @@
+// src/daemon.rs
+
+use crate::login::verify_credentials;
+use crate::vault::Vault;
+use dirs::{config_dir, runtime_dir};
+use hex::{decode, encode};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::io::AsRawFd;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+// One request per line on the socket
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(tag = "command", rename_all = "lowercase")]
+enum Request {
+    Encrypt { data: String },
+    Decrypt { data: String },
+    List,
+    Status,
+    Lock,
+}
+
+// One response line per request
+#[derive(Serialize, Default)]
+struct Response {
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    records: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    locked: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl Response {
+    fn error(error: String) -> Response {
+        Response {
+            error: Some(error),
+            ..Response::default()
+        }
+    }
+}
+
+// Session keys shared by every connection, dropped by the lock command
+type Session = Arc<Mutex<Option<Vault>>>;
+
+// Path of the daemon socket, in the per-user runtime directory when there is one
+pub fn socket_path() -> Result<PathBuf, String> {
+    let base = runtime_dir()
+        .or_else(config_dir)
+        .ok_or("Failed to get config directory")?;
+    Ok(base.join("lockbox").join("daemon.sock"))
+}
+
+// Entry point for `lockbox daemon [username]`
+pub fn run(username: Option<String>) -> Result<(), String> {
+    let username = match username {
+        Some(username) => username,
+        None => prompt("Username: ")?,
+    };
+    let password = rpassword::prompt_password("Password: ")
+        .map_err(|e| format!("Failed to read password: {:?}", e))?;
+
+    verify_credentials(&username, &password).map_err(|e| e.to_string())?;
+    let session: Session = Arc::new(Mutex::new(Some(Vault::unlock(&username, &password)?)));
+    drop(password);
+
+    let path = socket_path()?;
+    let dir = path.parent().ok_or("Invalid socket path")?;
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create socket directory: {:?}", e))?;
+    fs::set_permissions(dir, fs::Permissions::from_mode(0o700))
+        .map_err(|e| format!("Failed to secure socket directory: {:?}", e))?;
+
+    // A socket left behind by a crashed daemon blocks binding
+    if path.exists() {
+        if UnixStream::connect(&path).is_ok() {
+            return Err("A lockbox daemon is already running".to_string());
+        }
+        fs::remove_file(&path).map_err(|e| format!("Failed to remove stale socket: {:?}", e))?;
+    }
+
+    let listener =
+        UnixListener::bind(&path).map_err(|e| format!("Failed to bind socket: {:?}", e))?;
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o600))
+        .map_err(|e| format!("Failed to secure socket: {:?}", e))?;
+    println!("lockbox daemon listening on {}", path.display());
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("Failed to accept connection: {:?}", e);
+                continue;
+            }
+        };
+
+        if let Err(e) = authorize(&stream, unsafe { libc::geteuid() }) {
+            eprintln!("{}", e);
+            continue;
+        }
+
+        let session = Arc::clone(&session);
+        thread::spawn(move || {
+            if let Err(e) = serve(stream, session) {
+                eprintln!("Connection failed: {:?}", e);
+            }
+        });
+    }
+
+    Ok(())
+}
+
+fn serve(stream: UnixStream, session: Session) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => handle(request, &session),
+            Err(e) => Response::error(format!("Invalid request: {}", e)),
+        };
+        let mut encoded = serde_json::to_string(&response)?;
+        encoded.push('\n');
+        writer.write_all(encoded.as_bytes())?;
+    }
+    Ok(())
+}
+
+fn handle(request: Request, session: &Session) -> Response {
+    let mut session = match session.lock() {
+        Ok(session) => session,
+        Err(_) => return Response::error("Session is unavailable".to_string()),
+    };
+
+    let result = match request {
+        Request::Status => Ok(Response {
+            ok: true,
+            locked: Some(session.is_none()),
+            ..Response::default()
+        }),
+        Request::Lock => {
+            *session = None;
+            Ok(Response {
+                ok: true,
+                locked: Some(true),
+                ..Response::default()
+            })
+        }
+        Request::Encrypt { data } => unlocked(&session).and_then(|vault| {
+            decode(data)
+                .map_err(|_| "Data is not valid hex".to_string())
+                .and_then(|data| vault.encrypt(&data))
+                .map(|data| Response {
+                    ok: true,
+                    data: Some(encode(data)),
+                    ..Response::default()
+                })
+        }),
+        Request::Decrypt { data } => unlocked(&session).and_then(|vault| {
+            decode(data)
+                .map_err(|_| "Data is not valid hex".to_string())
+                .and_then(|data| vault.decrypt(&data))
+                .map(|data| Response {
+                    ok: true,
+                    data: Some(encode(data)),
+                    ..Response::default()
+                })
+        }),
+        Request::List => unlocked(&session)
+            .and_then(|vault| vault.records())
+            .map(|records| Response {
+                ok: true,
+                records: Some(records),
+                ..Response::default()
+            }),
+    };
+
+    result.unwrap_or_else(Response::error)
+}
+
+fn unlocked(session: &Option<Vault>) -> Result<&Vault, String> {
+    session
+        .as_ref()
+        .ok_or_else(|| "The daemon is locked".to_string())
+}
+
+// Only processes of the same user may talk to the daemon
+fn authorize(stream: &UnixStream, uid: libc::uid_t) -> Result<(), String> {
+    match peer_uid(stream)? {
+        peer if peer == uid => Ok(()),
+        peer => Err(format!("Rejected connection from uid {}", peer)),
+    }
+}
+
+// Uid of the process on the other end of the socket
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn peer_uid(stream: &UnixStream) -> Result<libc::uid_t, String> {
+    let mut credentials = libc::ucred {
+        pid: 0,
+        uid: 0,
+        gid: 0,
+    };
+    let mut len = std::mem::size_of::<libc::ucred>() as libc::socklen_t;
+    let result = unsafe {
+        libc::getsockopt(
+            stream.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_PEERCRED,
+            &mut credentials as *mut libc::ucred as *mut libc::c_void,
+            &mut len,
+        )
+    };
+    if result != 0 {
+        return Err(format!(
+            "Failed to read peer credentials: {:?}",
+            io::Error::last_os_error()
+        ));
+    }
+    Ok(credentials.uid)
+}
+
+// macOS and the BSDs expose the same check as getpeereid
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn peer_uid(stream: &UnixStream) -> Result<libc::uid_t, String> {
+    let mut uid = 0;
+    let mut gid = 0;
+    if unsafe { libc::getpeereid(stream.as_raw_fd(), &mut uid, &mut gid) } != 0 {
+        return Err(format!(
+            "Failed to read peer credentials: {:?}",
+            io::Error::last_os_error()
+        ));
+    }
+    Ok(uid)
+}
+
+fn prompt(label: &str) -> Result<String, String> {
+    print!("{}", label);
+    io::stdout()
+        .flush()
+        .map_err(|e| format!("Failed to write prompt: {:?}", e))?;
+    let mut value = String::new();
+    io::stdin()
+        .read_line(&mut value)
+        .map_err(|e| format!("Failed to read input: {:?}", e))?;
+    Ok(value.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session() -> (tempfile::TempDir, Session) {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::temporary(dir.path());
+        (dir, Arc::new(Mutex::new(Some(vault))))
+    }
+
+    fn request(line: &str) -> Request {
+        serde_json::from_str(line).unwrap()
+    }
+
+    #[test]
+    fn parses_requests() {
+        assert_eq!(
+            request(r#"{"command":"encrypt","data":"00ff"}"#),
+            Request::Encrypt {
+                data: "00ff".to_string()
+            }
+        );
+        assert_eq!(request(r#"{"command":"list"}"#), Request::List);
+        assert_eq!(request(r#"{"command":"status"}"#), Request::Status);
+        assert_eq!(request(r#"{"command":"lock"}"#), Request::Lock);
+        assert!(serde_json::from_str::<Request>(r#"{"command":"unlock"}"#).is_err());
+        assert!(serde_json::from_str::<Request>(r#"{"command":"decrypt"}"#).is_err());
+    }
+
+    #[test]
+    fn round_trips_data() {
+        let (_dir, session) = session();
+        let encrypted = handle(
+            request(r#"{"command":"encrypt","data":"68656c6c6f"}"#),
+            &session,
+        );
+        assert!(encrypted.ok);
+
+        let line = format!(
+            r#"{{"command":"decrypt","data":"{}"}}"#,
+            encrypted.data.unwrap()
+        );
+        let decrypted = handle(request(&line), &session);
+        assert_eq!(decrypted.data.as_deref(), Some("68656c6c6f"));
+
+        let invalid = handle(request(r#"{"command":"encrypt","data":"zz"}"#), &session);
+        assert_eq!(invalid.error.as_deref(), Some("Data is not valid hex"));
+    }
+
+    #[test]
+    fn status_and_lock_work_locked_or_not() {
+        let (_dir, session) = session();
+        assert_eq!(handle(Request::Status, &session).locked, Some(false));
+        assert_eq!(handle(Request::Lock, &session).locked, Some(true));
+        assert_eq!(handle(Request::Status, &session).locked, Some(true));
+        assert_eq!(handle(Request::Lock, &session).locked, Some(true));
+
+        let list = handle(Request::List, &session);
+        assert!(!list.ok);
+        assert_eq!(list.error.as_deref(), Some("The daemon is locked"));
+    }
+
+    #[test]
+    fn rejects_other_users() {
+        let (stream, _peer) = UnixStream::pair().unwrap();
+        let uid = unsafe { libc::geteuid() };
+        assert!(authorize(&stream, uid).is_ok());
+        assert_eq!(
+            authorize(&stream, uid + 1),
+            Err(format!("Rejected connection from uid {}", uid))
+        );
+    }
+}
@@ -0,0 +1,289 @@
+// src/dragdrop.rs
+
+use crate::store::{BlueBorderContainer, BlueButton, GreenButton};
+use crate::theme;
+use iced::{
+    alignment, button, container, Alignment, Background, Button, Color, Column, Container, Element,
+    Length, Row, Subscription, Text,
+};
+use iced_native::{window, Event};
+use std::path::{Path, PathBuf};
+
+// How many folders deep a dropped folder is searched for files
+const MAX_DEPTH: usize = 8;
+
+// Window events needed to route files dropped onto the store page
+pub fn events() -> Subscription<crate::Message> {
+    iced_native::subscription::events_with(|event, _status| match event {
+        Event::Window(window::Event::FileHovered(_)) => Some(crate::Message::FileHovered),
+        Event::Window(window::Event::FileDropped(path)) => Some(crate::Message::FileDropped(path)),
+        Event::Window(window::Event::FilesHoveredLeft) => Some(crate::Message::FilesHoveredLeft),
+        _ => None,
+    })
+}
+
+// Tracks a drag over the window and the files dropped so far. Most platforms
+// send no cursor position while the OS drags files, so a drop always selects
+// and what to do with it is chosen afterwards.
+#[derive(Default)]
+pub struct DropTarget {
+    hovering: bool,
+    hovered_files: usize,
+    dropped: Vec<PathBuf>,
+    // Files of the last drop, offered for encrypting or decrypting
+    last_drop: Vec<PathBuf>,
+    encrypt_button: button::State,
+    decrypt_button: button::State,
+    dismiss_button: button::State,
+}
+
+impl DropTarget {
+    pub fn is_hovering(&self) -> bool {
+        self.hovering
+    }
+
+    // Dropped files wait until the rest of their drop has arrived
+    pub fn is_waiting(&self) -> bool {
+        !self.dropped.is_empty()
+    }
+
+    // One event arrives per hovered file
+    pub fn file_hovered(&mut self) {
+        if !self.hovering {
+            self.hovering = true;
+            self.hovered_files = 0;
+        }
+        self.hovered_files += 1;
+    }
+
+    pub fn hover_left(&mut self) {
+        self.hovering = false;
+    }
+
+    // One event arrives per dropped file
+    pub fn dropped(&mut self, path: PathBuf) {
+        self.dropped.push(path);
+        self.hovering = false;
+    }
+
+    // The whole drop
+    pub fn take(&mut self) -> Vec<PathBuf> {
+        std::mem::take(&mut self.dropped)
+    }
+
+    // Offer the files found in a drop until they are acted on or dismissed
+    pub fn offer(&mut self, files: Vec<PathBuf>) {
+        self.last_drop = files;
+    }
+
+    pub fn dismiss(&mut self) {
+        self.last_drop.clear();
+    }
+
+    // The choice only applies while the drop is still what is selected
+    fn is_offered(&self, selection: &[PathBuf]) -> bool {
+        !self.last_drop.is_empty() && self.last_drop == selection
+    }
+
+    // The whole window is the target while files are dragged over it
+    pub fn view(&self) -> Element<'_, crate::Message> {
+        let files = if self.hovered_files == 1 {
+            "1 file".to_string()
+        } else {
+            format!("{} files", self.hovered_files)
+        };
+        let content = Column::new()
+            .spacing(10)
+            .align_items(Alignment::Center)
+            .push(Text::new(format!("Drop {} to select them", files)).size(28))
+            .push(Text::new("Then choose to encrypt or decrypt").size(16));
+
+        Container::new(
+            Container::new(content)
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .align_x(alignment::Horizontal::Center)
+                .align_y(alignment::Vertical::Center)
+                .style(DropAreaStyle),
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .padding(10)
+        .into()
+    }
+
+    // Encrypt or decrypt the files just dropped, shown below the file buttons
+    pub fn choice_view(&mut self, selection: &[PathBuf]) -> Option<Element<'_, crate::Message>> {
+        if !self.is_offered(selection) {
+            return None;
+        }
+        let files = if self.last_drop.len() == 1 {
+            "1 dropped file".to_string()
+        } else {
+            format!("{} dropped files", self.last_drop.len())
+        };
+        let row = Row::new()
+            .spacing(10)
+            .align_items(Alignment::Center)
+            .push(Text::new(files).size(16))
+            .push(
+                Button::new(&mut self.encrypt_button, Text::new("Encrypt").size(16))
+                    .style(GreenButton)
+                    .on_press(crate::Message::EncryptFile),
+            )
+            .push(
+                Button::new(&mut self.decrypt_button, Text::new("Decrypt").size(16))
+                    .style(BlueButton)
+                    .on_press(crate::Message::DecryptFile),
+            )
+            .push(
+                Button::new(&mut self.dismiss_button, Text::new("Dismiss").size(16))
+                    .on_press(crate::Message::DismissDrop),
+            );
+        Some(
+            Container::new(row)
+                .style(BlueBorderContainer)
+                .padding(10)
+                .into(),
+        )
+    }
+}
+
+// Expand dropped folders into the files inside them, skipping hidden entries and
+// symlinks, which could point outside the folder or loop back into it
+pub fn collect_files(path: &Path, files: &mut Vec<PathBuf>) {
+    collect_within(path, MAX_DEPTH, files);
+}
+
+fn collect_within(path: &Path, depth: usize, files: &mut Vec<PathBuf>) {
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(_) => return,
+    };
+    if metadata.is_file() {
+        files.push(path.to_path_buf());
+        return;
+    }
+    if !metadata.is_dir() || depth == 0 {
+        return;
+    }
+    if let Ok(entries) = std::fs::read_dir(path) {
+        let mut children: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|child| {
+                !child
+                    .file_name()
+                    .map(|name| name.to_string_lossy().starts_with('.'))
+                    .unwrap_or(true)
+            })
+            .collect();
+        children.sort();
+        for child in children {
+            collect_within(&child, depth - 1, files);
+        }
+    }
+}
+
+struct DropAreaStyle;
+
+impl container::StyleSheet for DropAreaStyle {
+    fn style(&self) -> container::Style {
+        let blue = theme::palette().border;
+        container::Style {
+            background: Some(Background::Color(Color { a: 0.2, ..blue })),
+            border_width: 3.0,
+            border_color: blue,
+            border_radius: 5.0,
+            ..container::Style::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn drop_collects_every_file() {
+        let mut target = DropTarget::default();
+        target.file_hovered();
+        target.file_hovered();
+        assert!(target.is_hovering());
+        target.dropped(PathBuf::from("a"));
+        target.dropped(PathBuf::from("b"));
+        assert!(!target.is_hovering());
+        assert!(target.is_waiting());
+        assert_eq!(target.take(), vec![PathBuf::from("a"), PathBuf::from("b")]);
+        assert!(!target.is_waiting());
+    }
+
+    #[test]
+    fn choice_follows_the_selection() {
+        let mut target = DropTarget::default();
+        let files = vec![PathBuf::from("a"), PathBuf::from("b")];
+        target.offer(files.clone());
+        assert!(target.is_offered(&files));
+        // Picking other files replaces the drop
+        assert!(!target.is_offered(&[PathBuf::from("c")]));
+        target.dismiss();
+        assert!(!target.is_offered(&files));
+        // An empty drop offers nothing
+        target.offer(Vec::new());
+        assert!(!target.is_offered(&[]));
+    }
+
+    #[test]
+    fn folders_expand_to_visible_files() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("sub")).unwrap();
+        fs::create_dir_all(dir.path().join(".git")).unwrap();
+        fs::write(dir.path().join("b.txt"), "b").unwrap();
+        fs::write(dir.path().join("sub/a.txt"), "a").unwrap();
+        fs::write(dir.path().join(".hidden"), "h").unwrap();
+        fs::write(dir.path().join(".git/config"), "c").unwrap();
+
+        let mut files = Vec::new();
+        collect_files(dir.path(), &mut files);
+        assert_eq!(
+            files,
+            vec![dir.path().join("b.txt"), dir.path().join("sub/a.txt")]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        fs::write(outside.path().join("secret.txt"), "s").unwrap();
+        fs::write(dir.path().join("file.txt"), "f").unwrap();
+        std::os::unix::fs::symlink(outside.path(), dir.path().join("linked_dir")).unwrap();
+        std::os::unix::fs::symlink(
+            outside.path().join("secret.txt"),
+            dir.path().join("linked.txt"),
+        )
+        .unwrap();
+        // A link back to the dropped folder would otherwise never end
+        std::os::unix::fs::symlink(dir.path(), dir.path().join("loop")).unwrap();
+
+        let mut files = Vec::new();
+        collect_files(dir.path(), &mut files);
+        assert_eq!(files, vec![dir.path().join("file.txt")]);
+    }
+
+    #[test]
+    fn deep_folders_stop_at_the_depth_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut path = dir.path().to_path_buf();
+        for level in 0..=MAX_DEPTH {
+            fs::create_dir(path.join("d")).unwrap();
+            fs::write(path.join(format!("{}.txt", level)), "x").unwrap();
+            path = path.join("d");
+        }
+
+        let mut files = Vec::new();
+        collect_files(dir.path(), &mut files);
+        assert_eq!(files.len(), MAX_DEPTH);
+    }
+}
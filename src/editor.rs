@@ -0,0 +1,287 @@
+// src/editor.rs
+
+use iced::{scrollable, text_input, Column, Element, Length, Scrollable, TextInput};
+use iced_native::widget::text_input::{cursor, Value};
+
+// One line of the editor, each backed by its own text input
+#[derive(Default)]
+struct Line {
+    text: String,
+    state: text_input::State,
+}
+
+// Multi-line text built from one text input per line, since iced has no text area.
+// Enter splits a line, Backspace and Delete at its edges join lines back together.
+pub struct Editor {
+    lines: Vec<Line>,
+    scroll: scrollable::State,
+}
+
+impl Default for Editor {
+    fn default() -> Self {
+        Self {
+            lines: vec![Line::default()],
+            scroll: scrollable::State::new(),
+        }
+    }
+}
+
+impl Editor {
+    pub fn new(text: &str) -> Self {
+        Self {
+            lines: text
+                .split('\n')
+                .map(|line| Line {
+                    text: line.to_string(),
+                    state: text_input::State::new(),
+                })
+                .collect(),
+            scroll: scrollable::State::new(),
+        }
+    }
+
+    pub fn text(&self) -> String {
+        self.lines
+            .iter()
+            .map(|line| line.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.iter().all(|line| line.text.is_empty())
+    }
+
+    // Apply a change from a line, returning whether the text changed
+    pub fn edit(&mut self, index: usize, value: String) -> bool {
+        let line = match self.lines.get_mut(index) {
+            Some(line) => line,
+            None => return false,
+        };
+        if value != line.text {
+            line.text = value;
+            return true;
+        }
+
+        // An unchanged line means Backspace at its start or Delete at its end
+        let len = Value::new(&line.text).len();
+        match line.state.cursor().state(&Value::new(&line.text)) {
+            cursor::State::Index(0) if index > 0 => {
+                let line = self.lines.remove(index);
+                let previous = &mut self.lines[index - 1];
+                let position = Value::new(&previous.text).len();
+                previous.text.push_str(&line.text);
+                self.focus(index - 1, position);
+                true
+            }
+            cursor::State::Index(position) if position == len && index + 1 < self.lines.len() => {
+                let next = self.lines.remove(index + 1);
+                self.lines[index].text.push_str(&next.text);
+                self.focus(index, position);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // Enter splits the line at the cursor, dropping any selected text
+    pub fn split(&mut self, index: usize) {
+        let line = match self.lines.get_mut(index) {
+            Some(line) => line,
+            None => return,
+        };
+        let value = Value::new(&line.text);
+        let (start, end) = match line.state.cursor().state(&value) {
+            cursor::State::Index(position) => (position, position),
+            cursor::State::Selection { start, end } => (start.min(end), start.max(end)),
+        };
+        line.text = value.until(start).to_string();
+        self.lines.insert(
+            index + 1,
+            Line {
+                text: value.select(end, value.len()).to_string(),
+                state: text_input::State::new(),
+            },
+        );
+        self.focus(index + 1, 0);
+    }
+
+    // Insert text that may hold several lines at the cursor, or at the end without focus.
+    // Pasting into a line itself drops the line breaks, which armored text needs.
+    pub fn paste(&mut self, text: &str) {
+        let index = self
+            .lines
+            .iter()
+            .position(|line| line.state.is_focused())
+            .unwrap_or(self.lines.len() - 1);
+        let line = &mut self.lines[index];
+        let value = Value::new(&line.text);
+        let (start, end) = match line.state.cursor().state(&value) {
+            _ if !line.state.is_focused() => (value.len(), value.len()),
+            cursor::State::Index(position) => (position, position),
+            cursor::State::Selection { start, end } => (start.min(end), start.max(end)),
+        };
+        let tail = value.select(end, value.len()).to_string();
+        let combined = format!(
+            "{}{}{}",
+            value.until(start).to_string(),
+            text.replace("\r\n", "\n"),
+            tail
+        );
+
+        let inserted: Vec<Line> = combined
+            .split('\n')
+            .map(|text| Line {
+                text: text.to_string(),
+                state: text_input::State::new(),
+            })
+            .collect();
+        let last = index + inserted.len() - 1;
+        self.lines.splice(index..=index, inserted);
+        let position = Value::new(&self.lines[last].text).len() - Value::new(&tail).len();
+        self.focus(last, position);
+    }
+
+    // Up and Down move between lines, which text inputs leave alone
+    pub fn move_focus(&mut self, down: bool) {
+        let index = match self.lines.iter().position(|line| line.state.is_focused()) {
+            Some(index) => index,
+            None => return,
+        };
+        let target = if down {
+            index + 1
+        } else {
+            match index.checked_sub(1) {
+                Some(target) => target,
+                None => return,
+            }
+        };
+        if target >= self.lines.len() {
+            return;
+        }
+        let line = &self.lines[index];
+        let column = match line.state.cursor().state(&Value::new(&line.text)) {
+            cursor::State::Index(position) => position,
+            cursor::State::Selection { end, .. } => end,
+        };
+        let column = column.min(Value::new(&self.lines[target].text).len());
+        self.focus(target, column);
+    }
+
+    fn focus(&mut self, index: usize, position: usize) {
+        for line in &mut self.lines {
+            line.state.unfocus();
+        }
+        let state = &mut self.lines[index].state;
+        state.focus();
+        state.move_cursor_to(position);
+    }
+
+    pub fn view<'a>(
+        &'a mut self,
+        placeholder: &str,
+        height: u16,
+        on_edit: fn(usize, String) -> crate::Message,
+        on_enter: fn(usize) -> crate::Message,
+    ) -> Element<'a, crate::Message> {
+        // The placeholder only makes sense while there is a single line
+        let single = self.lines.len() == 1;
+        let mut lines = Column::new();
+        for (index, line) in self.lines.iter_mut().enumerate() {
+            lines = lines.push(
+                TextInput::new(
+                    &mut line.state,
+                    if single { placeholder } else { "" },
+                    &line.text,
+                    move |value| on_edit(index, value),
+                )
+                .on_submit(on_enter(index))
+                .padding(4)
+                .size(16),
+            );
+        }
+
+        Scrollable::new(&mut self.scroll)
+            .width(Length::Fill)
+            .height(Length::Units(height))
+            .push(lines)
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn editor(lines: &[&str]) -> Editor {
+        Editor::new(&lines.join("\n"))
+    }
+
+    #[test]
+    fn new_and_text_round_trip() {
+        let text = "first\n\nthird\n";
+        assert_eq!(Editor::new(text).text(), text);
+        assert!(Editor::new("\n").is_empty());
+    }
+
+    #[test]
+    fn paste_keeps_line_breaks() {
+        let mut editor = editor(&["-----BEGIN", "body"]);
+        editor.paste("\r\nEND-----");
+        assert_eq!(editor.text(), "-----BEGIN\nbody\nEND-----");
+        assert_eq!(editor.lines.len(), 3);
+    }
+
+    #[test]
+    fn paste_at_cursor() {
+        let mut editor = editor(&["hello world"]);
+        editor.focus(0, 5);
+        editor.paste(",\nbig");
+        assert_eq!(editor.text(), "hello,\nbig world");
+        assert!(editor.lines[1].state.is_focused());
+    }
+
+    #[test]
+    fn enter_splits_at_cursor() {
+        let mut editor = editor(&["hello world"]);
+        editor.focus(0, 5);
+        editor.split(0);
+        assert_eq!(editor.text(), "hello\n world");
+        assert!(editor.lines[1].state.is_focused());
+    }
+
+    #[test]
+    fn backspace_at_start_joins_previous_line() {
+        let mut editor = editor(&["one", "two"]);
+        editor.focus(1, 0);
+        assert!(editor.edit(1, "two".to_string()));
+        assert_eq!(editor.text(), "onetwo");
+        assert!(editor.lines[0].state.is_focused());
+    }
+
+    #[test]
+    fn delete_at_end_joins_next_line() {
+        let mut editor = editor(&["one", "two"]);
+        editor.focus(0, 3);
+        assert!(editor.edit(0, "one".to_string()));
+        assert_eq!(editor.text(), "onetwo");
+    }
+
+    #[test]
+    fn unchanged_line_in_the_middle_is_left_alone() {
+        let mut editor = editor(&["one", "two"]);
+        editor.focus(0, 1);
+        assert!(!editor.edit(0, "one".to_string()));
+        assert_eq!(editor.text(), "one\ntwo");
+    }
+
+    #[test]
+    fn arrows_keep_the_column() {
+        let mut editor = editor(&["long line", "ab"]);
+        editor.focus(0, 6);
+        editor.move_focus(true);
+        assert!(editor.lines[1].state.is_focused());
+        editor.split(1);
+        assert_eq!(editor.text(), "long line\nab\n");
+    }
+}
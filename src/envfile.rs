@@ -0,0 +1,397 @@
+// src/envfile.rs
+
+use crate::session;
+use crate::store::{decrypt_bytes, encrypt_bytes, BlueBorderContainer, BlueButton, GreenButton};
+use age::armor::Format;
+use iced::{
+    alignment, button, scrollable, text_input, Alignment, Button, Color, Column, Container,
+    Element, Image, Length, Row, Scrollable, Text, TextInput,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use zeroize::Zeroize;
+
+// A single KEY=value line
+#[derive(Default, Clone)]
+pub struct EnvVar {
+    pub key: String,
+    pub value: String,
+}
+
+impl Drop for EnvVar {
+    fn drop(&mut self) {
+        self.value.zeroize();
+    }
+}
+
+// Decrypt an encrypted .env file and parse it without touching disk
+pub fn load(path: &Path) -> Result<Vec<EnvVar>, String> {
+    let encrypted =
+        fs::read(path).map_err(|e| format!("Failed to read {}: {:?}", path.display(), e))?;
+    let mut plaintext = decrypt_bytes(&encrypted)?;
+    let vars = dotenvy::from_read_iter(plaintext.as_slice())
+        .map(|item| {
+            item.map(|(key, value)| EnvVar { key, value })
+                .map_err(|e| format!("Failed to parse env file: {}", e))
+        })
+        .collect();
+    plaintext.zeroize();
+    vars
+}
+
+// Encrypt variables back into a .env file with the store passphrase
+pub fn save(path: &Path, vars: &[EnvVar]) -> Result<(), String> {
+    let mut plaintext = String::new();
+    for var in vars.iter().filter(|var| !var.key.trim().is_empty()) {
+        plaintext.push_str(&format!("{}={}\n", var.key.trim(), quote(&var.value)));
+    }
+    let encrypted = encrypt_bytes(plaintext.as_bytes(), Format::Binary);
+    plaintext.zeroize();
+
+    // Replace the file atomically so a crash never leaves it half written
+    let tmp = path.with_extension("lockbox-tmp");
+    fs::write(&tmp, encrypted?)
+        .and_then(|_| fs::rename(&tmp, path))
+        .map_err(|e| format!("Failed to write {}: {:?}", path.display(), e))
+}
+
+// Quote a value so dotenv parsing returns it unchanged
+fn quote(value: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '\\' | '"' | '$' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '\n' => quoted.push_str("\\n"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+// Entry point for `lockbox run --env <file> -- <command> [args...]`
+pub fn run(args: &[String]) -> Result<(), String> {
+    let usage = "Usage: lockbox run --env <file.env.age> -- <command> [args...]";
+    let mut env_files = Vec::new();
+    let mut rest = args.iter();
+    loop {
+        match rest.next().map(String::as_str) {
+            Some("--env") => env_files.push(PathBuf::from(rest.next().ok_or(usage)?)),
+            Some("--") => break,
+            _ => return Err(usage.to_string()),
+        }
+    }
+    let command: Vec<&String> = rest.collect();
+    let (program, program_args) = command.split_first().ok_or(usage)?;
+
+    let mut child = Command::new(program);
+    child.args(program_args);
+    for path in &env_files {
+        for var in load(path)? {
+            child.env(&var.key, &var.value);
+        }
+    }
+
+    let status = child
+        .status()
+        .map_err(|e| format!("Failed to run {}: {:?}", program, e))?;
+    std::process::exit(exit_code(status));
+}
+
+// Exit code to forward, following the shell convention for signals
+fn exit_code(status: std::process::ExitStatus) -> i32 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+    status.code().unwrap_or(1)
+}
+
+// Define the state of the encrypted env file editor
+#[derive(Default)]
+pub struct EnvPage {
+    path: Option<PathBuf>,
+    vars: Vec<EnvVar>,
+    inputs: Vec<(text_input::State, text_input::State, button::State)>,
+    revealed: bool,
+    dirty: bool,
+    status: Option<String>,
+    scroll: scrollable::State,
+    new_button: button::State,
+    open_button: button::State,
+    save_button: button::State,
+    add_button: button::State,
+    reveal_button: button::State,
+    back_button: button::State,
+}
+
+impl EnvPage {
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    pub fn new_file(&mut self) {
+        *self = Self {
+            vars: vec![EnvVar::default()],
+            ..Self::default()
+        };
+    }
+
+    pub fn open_file(&mut self) {
+        let path = match session::open_dialog()
+            .add_filter("Encrypted env", &["age"])
+            .pick_file()
+        {
+            Some(path) => path,
+            None => return,
+        };
+        session::remember_open(&path);
+
+        match load(&path) {
+            Ok(vars) => {
+                *self = Self::default();
+                self.status = Some(format!("Opened {}", path.display()));
+                self.vars = vars;
+                self.path = Some(path);
+            }
+            Err(e) => self.status = Some(e),
+        }
+    }
+
+    pub fn save_file(&mut self) {
+        if self.path.is_none() {
+            self.path = session::output_dialog()
+                .add_filter("Encrypted env", &["age"])
+                .set_file_name("secrets.env.age")
+                .save_file();
+            if let Some(path) = &self.path {
+                session::remember_output(path);
+            }
+        }
+        let path = match &self.path {
+            Some(path) => path,
+            None => return,
+        };
+
+        self.status = Some(match save(path, &self.vars) {
+            Ok(()) => {
+                self.dirty = false;
+                format!("Saved {}", path.display())
+            }
+            Err(e) => e,
+        });
+    }
+
+    pub fn add_var(&mut self) {
+        self.vars.push(EnvVar::default());
+        self.dirty = true;
+    }
+
+    pub fn remove_var(&mut self, index: usize) {
+        if index < self.vars.len() {
+            self.vars.remove(index);
+            self.dirty = true;
+        }
+    }
+
+    pub fn key_changed(&mut self, index: usize, value: String) {
+        if let Some(var) = self.vars.get_mut(index) {
+            var.key = value;
+            self.dirty = true;
+        }
+    }
+
+    pub fn value_changed(&mut self, index: usize, value: String) {
+        if let Some(var) = self.vars.get_mut(index) {
+            var.value = value;
+            self.dirty = true;
+        }
+    }
+
+    pub fn toggle_reveal(&mut self) {
+        self.revealed = !self.revealed;
+    }
+
+    pub fn view(&mut self) -> Element<'_, crate::Message> {
+        let logo = Container::new(
+            Image::new("images/logo.png")
+                .width(Length::Units(40))
+                .height(Length::Units(40)),
+        )
+        .padding(10)
+        .align_x(alignment::Horizontal::Center);
+
+        let title = match &self.path {
+            Some(path) => path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            None => "New env file".to_string(),
+        };
+        let title = if self.dirty {
+            format!("{} *", title)
+        } else {
+            title
+        };
+
+        let toolbar = Row::new()
+            .spacing(10)
+            .push(
+                Button::new(&mut self.new_button, Text::new("New").size(16))
+                    .style(BlueButton)
+                    .on_press(crate::Message::NewEnvFile),
+            )
+            .push(
+                Button::new(&mut self.open_button, Text::new("Open").size(16))
+                    .style(BlueButton)
+                    .on_press(crate::Message::OpenEnvFile),
+            )
+            .push(
+                Button::new(&mut self.save_button, Text::new("Save").size(16))
+                    .style(GreenButton)
+                    .on_press(crate::Message::SaveEnvFile),
+            )
+            .push(
+                Button::new(
+                    &mut self.reveal_button,
+                    Text::new(if self.revealed { "Hide" } else { "Show" }).size(16),
+                )
+                .style(BlueButton)
+                .on_press(crate::Message::ToggleRevealEnv),
+            );
+
+        // Keep one set of widget states per variable
+        self.inputs.resize_with(self.vars.len(), Default::default);
+        let mut rows = Scrollable::new(&mut self.scroll)
+            .spacing(5)
+            .height(Length::Units(300))
+            .width(Length::Units(600));
+        for (index, (var, (key_input, value_input, remove_button))) in
+            self.vars.iter().zip(self.inputs.iter_mut()).enumerate()
+        {
+            let mut value = TextInput::new(value_input, "value", &var.value, move |value| {
+                crate::Message::EnvValueChanged(index, value)
+            })
+            .padding(8)
+            .size(16)
+            .width(Length::Units(320));
+            if !self.revealed {
+                value = value.password();
+            }
+
+            rows = rows.push(
+                Row::new()
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .push(
+                        TextInput::new(key_input, "KEY", &var.key, move |key| {
+                            crate::Message::EnvKeyChanged(index, key)
+                        })
+                        .padding(8)
+                        .size(16)
+                        .width(Length::Units(180)),
+                    )
+                    .push(value)
+                    .push(
+                        Button::new(remove_button, Text::new("Remove").size(14))
+                            .style(BlueButton)
+                            .on_press(crate::Message::RemoveEnvVar(index)),
+                    ),
+            );
+        }
+
+        let mut content = Column::new()
+            .spacing(20)
+            .align_items(Alignment::Center)
+            .push(Text::new("Env Files").size(24))
+            .push(Text::new(title).size(18))
+            .push(toolbar)
+            .push(Container::new(rows).style(BlueBorderContainer).padding(10))
+            .push(
+                Button::new(&mut self.add_button, Text::new("Add Variable").size(16))
+                    .style(BlueButton)
+                    .on_press(crate::Message::AddEnvVar),
+            )
+            .push(
+                Text::new(
+                    "Run a command with these variables: lockbox run --env <file> -- <command>",
+                )
+                .size(14)
+                .color(Color::from_rgb(0.5, 0.5, 0.5)),
+            );
+
+        if let Some(status) = &self.status {
+            content = content.push(
+                Text::new(status)
+                    .size(14)
+                    .color(Color::from_rgb(0.5, 0.5, 0.5)),
+            );
+        }
+
+        content = content.push(
+            Button::new(&mut self.back_button, Text::new("Back").size(16))
+                .style(BlueButton)
+                .on_press(crate::Message::SwitchToStorePage),
+        );
+
+        let container = Container::new(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y();
+
+        Column::new().push(logo).push(container).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // What `load` reads back for a value written by `save`
+    fn round_trip(value: &str) -> String {
+        let line = format!("KEY={}\n", quote(value));
+        let mut vars = dotenvy::from_read_iter(line.as_bytes());
+        let (key, parsed) = vars.next().unwrap().unwrap();
+        assert_eq!(key, "KEY");
+        assert!(vars.next().is_none());
+        parsed
+    }
+
+    #[test]
+    fn quoted_values_parse_back_unchanged() {
+        for value in [
+            "",
+            "plain",
+            "with spaces  ",
+            "  leading",
+            "say \"hi\"",
+            "it's",
+            "back\\slash\\",
+            "$HOME and ${PATH}",
+            "price: 5$",
+            "# not a comment",
+            "a=b=c",
+            "line one\nline two\n",
+            "tab\there",
+            "carriage\r\nreturn",
+            "ünïcødé ✓",
+            "\\n stays literal",
+        ] {
+            assert_eq!(round_trip(value), value, "value {:?}", value);
+        }
+    }
+
+    #[test]
+    fn dollar_signs_are_not_expanded() {
+        std::env::set_var("LOCKBOX_QUOTE_TEST", "expanded");
+        assert_eq!(round_trip("$LOCKBOX_QUOTE_TEST"), "$LOCKBOX_QUOTE_TEST");
+    }
+}
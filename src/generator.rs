@@ -0,0 +1,271 @@
+// src/generator.rs
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+// EFF large diceware word list, bundled with the binary
+const WORDLIST: &str = include_str!("../assets/eff_large_wordlist.txt");
+
+const LOWERCASE: &str = "abcdefghijklmnopqrstuvwxyz";
+const UPPERCASE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const DIGITS: &str = "0123456789";
+const SYMBOLS: &str = "!@#$%^&*()-_=+[]{};:,.<>/?";
+
+// Character classes a generated password can draw from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharClass {
+    Lowercase,
+    Uppercase,
+    Digits,
+    Symbols,
+}
+
+// Options for the password and passphrase generator
+#[derive(Debug, Clone)]
+pub struct GeneratorOptions {
+    pub passphrase: bool,
+    pub length: u8,
+    pub words: u8,
+    pub lowercase: bool,
+    pub uppercase: bool,
+    pub digits: bool,
+    pub symbols: bool,
+}
+
+impl Default for GeneratorOptions {
+    fn default() -> Self {
+        Self {
+            passphrase: false,
+            length: 20,
+            words: 6,
+            lowercase: true,
+            uppercase: true,
+            digits: true,
+            symbols: true,
+        }
+    }
+}
+
+impl GeneratorOptions {
+    pub fn class_enabled(&self, class: CharClass) -> bool {
+        match class {
+            CharClass::Lowercase => self.lowercase,
+            CharClass::Uppercase => self.uppercase,
+            CharClass::Digits => self.digits,
+            CharClass::Symbols => self.symbols,
+        }
+    }
+
+    pub fn set_class(&mut self, class: CharClass, enabled: bool) {
+        match class {
+            CharClass::Lowercase => self.lowercase = enabled,
+            CharClass::Uppercase => self.uppercase = enabled,
+            CharClass::Digits => self.digits = enabled,
+            CharClass::Symbols => self.symbols = enabled,
+        }
+    }
+}
+
+// Generate a password or diceware passphrase from the given options
+pub fn generate(options: &GeneratorOptions) -> Result<String, String> {
+    if options.passphrase {
+        generate_passphrase(options.words)
+    } else {
+        generate_password(options)
+    }
+}
+
+fn generate_password(options: &GeneratorOptions) -> Result<String, String> {
+    let classes: Vec<&str> = [
+        (options.lowercase, LOWERCASE),
+        (options.uppercase, UPPERCASE),
+        (options.digits, DIGITS),
+        (options.symbols, SYMBOLS),
+    ]
+    .iter()
+    .filter(|(enabled, _)| *enabled)
+    .map(|(_, chars)| *chars)
+    .collect();
+
+    if classes.is_empty() {
+        return Err("Select at least one character class".to_string());
+    }
+    if (options.length as usize) < classes.len() {
+        return Err("Password is too short for the selected classes".to_string());
+    }
+
+    let mut rng = rand::rngs::OsRng;
+    let all: Vec<char> = classes.iter().flat_map(|chars| chars.chars()).collect();
+
+    // Guarantee one character of every selected class, then fill the rest
+    let mut password: Vec<char> = classes
+        .iter()
+        .map(|chars| {
+            let chars: Vec<char> = chars.chars().collect();
+            chars[rng.gen_range(0..chars.len())]
+        })
+        .collect();
+    while password.len() < options.length as usize {
+        password.push(all[rng.gen_range(0..all.len())]);
+    }
+    password.shuffle(&mut rng);
+
+    Ok(password.into_iter().collect())
+}
+
+fn generate_passphrase(words: u8) -> Result<String, String> {
+    if words == 0 {
+        return Err("Select at least one word".to_string());
+    }
+
+    let list: Vec<&str> = WORDLIST
+        .lines()
+        .filter_map(|line| line.split('\t').nth(1))
+        .collect();
+
+    let mut rng = rand::rngs::OsRng;
+    let passphrase: Vec<&str> = (0..words)
+        .filter_map(|_| list.choose(&mut rng).copied())
+        .collect();
+
+    Ok(passphrase.join("-"))
+}
+
+// Estimated strength of a password
+pub struct Strength {
+    pub score: u8,
+    pub feedback: Option<String>,
+}
+
+impl Strength {
+    pub fn label(&self) -> &'static str {
+        score_label(self.score)
+    }
+}
+
+// Name of a zxcvbn score from 0 to 4
+pub fn score_label(score: u8) -> &'static str {
+    match score {
+        0 => "Very weak",
+        1 => "Weak",
+        2 => "Fair",
+        3 => "Strong",
+        _ => "Very strong",
+    }
+}
+
+// Estimate password strength with zxcvbn, penalizing the given user inputs
+pub fn strength(password: &str, user_inputs: &[&str]) -> Strength {
+    let entropy = zxcvbn::zxcvbn(password, user_inputs);
+    Strength {
+        score: entropy.score().into(),
+        feedback: entropy
+            .feedback()
+            .map(|feedback| feedback.to_string())
+            .filter(|feedback| !feedback.trim().is_empty()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(length: u8, classes: &[CharClass]) -> GeneratorOptions {
+        let mut options = GeneratorOptions {
+            length,
+            lowercase: false,
+            uppercase: false,
+            digits: false,
+            symbols: false,
+            ..GeneratorOptions::default()
+        };
+        for class in classes {
+            options.set_class(*class, true);
+        }
+        options
+    }
+
+    #[test]
+    fn passwords_have_the_requested_length_and_classes() {
+        let all = [
+            CharClass::Lowercase,
+            CharClass::Uppercase,
+            CharClass::Digits,
+            CharClass::Symbols,
+        ];
+        for length in [4, 20, 64] {
+            let password = generate(&options(length, &all)).unwrap();
+            assert_eq!(password.chars().count(), length as usize);
+            // Every selected class shows up, even in the shortest password
+            for chars in [LOWERCASE, UPPERCASE, DIGITS, SYMBOLS] {
+                assert!(password.chars().any(|c| chars.contains(c)), "{}", password);
+            }
+        }
+    }
+
+    #[test]
+    fn passwords_only_use_selected_classes() {
+        let password = generate(&options(32, &[CharClass::Digits])).unwrap();
+        assert!(password.chars().all(|c| DIGITS.contains(c)));
+
+        let password = generate(&options(32, &[CharClass::Lowercase, CharClass::Symbols])).unwrap();
+        assert!(password
+            .chars()
+            .all(|c| LOWERCASE.contains(c) || SYMBOLS.contains(c)));
+    }
+
+    #[test]
+    fn impossible_options_are_refused() {
+        assert!(generate(&options(20, &[])).is_err());
+        let all = [
+            CharClass::Lowercase,
+            CharClass::Uppercase,
+            CharClass::Digits,
+        ];
+        assert!(generate(&options(2, &all)).is_err());
+        assert!(generate(&options(3, &all)).is_ok());
+    }
+
+    #[test]
+    fn passphrases_have_the_requested_words() {
+        let options = GeneratorOptions {
+            passphrase: true,
+            words: 7,
+            ..GeneratorOptions::default()
+        };
+        // A few list words such as "t-shirt" hold a dash themselves
+        let tokens: Vec<&str> = WORDLIST
+            .lines()
+            .filter_map(|line| line.split('\t').nth(1))
+            .flat_map(|word| word.split('-'))
+            .collect();
+        let passphrase = generate(&options).unwrap();
+        let words: Vec<&str> = passphrase.split('-').collect();
+        assert!((7..=14).contains(&words.len()), "{}", passphrase);
+        assert!(words.iter().all(|word| tokens.contains(word)));
+
+        let single = GeneratorOptions {
+            words: 1,
+            ..options.clone()
+        };
+        let word = generate(&single).unwrap();
+        assert!(WORDLIST
+            .lines()
+            .any(|line| line.split('\t').nth(1) == Some(word.as_str())));
+
+        let none = GeneratorOptions {
+            words: 0,
+            ..options
+        };
+        assert!(generate(&none).is_err());
+    }
+
+    #[test]
+    fn scores_map_to_labels() {
+        assert_eq!(score_label(0), "Very weak");
+        assert_eq!(score_label(2), "Fair");
+        assert_eq!(score_label(4), "Very strong");
+        assert!(strength("password", &[]).score <= 1);
+        assert_eq!(strength("alice2024", &["alice2024"]).score, 0);
+    }
+}
@@ -0,0 +1,231 @@
+// src/gitfilter.rs
+
+use crate::store::{decrypt_with_identity, encrypt_to_recipients};
+use age::secrecy::ExposeSecret;
+use age::x25519;
+use sha2::{Digest, Sha256};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read, Write};
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// Name of the filter in .git/config and .gitattributes
+const FILTER: &str = "lockbox";
+
+// Header every binary age file starts with
+const AGE_HEADER: &[u8] = b"age-encryption.org/v1";
+
+// Entry point for `lockbox git init [pattern...]`
+pub fn init(patterns: &[String]) -> Result<(), String> {
+    let git_dir = git_dir()?;
+    let key_dir = git_dir.join(FILTER);
+    fs::create_dir_all(key_dir.join("cache"))
+        .map_err(|e| format!("Failed to create {}: {:?}", key_dir.display(), e))?;
+
+    let identity_path = key_dir.join("identity");
+    if identity_path.exists() {
+        println!("Using existing key {}", identity_path.display());
+    } else {
+        let identity = x25519::Identity::generate();
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+        options
+            .open(&identity_path)
+            .and_then(|mut file| writeln!(file, "{}", identity.to_string().expose_secret()))
+            .map_err(|e| format!("Failed to write repository key: {:?}", e))?;
+        println!("Created repository key {}", identity_path.display());
+    }
+
+    // Register the filter using this executable
+    let exe = std::env::current_exe()
+        .map_err(|e| format!("Failed to locate lockbox executable: {:?}", e))?;
+    let exe = format!("\"{}\"", exe.display());
+    git_config("clean", &format!("{} git-filter clean", exe))?;
+    git_config("smudge", &format!("{} git-filter smudge", exe))?;
+    git_config("required", "true")?;
+
+    if !patterns.is_empty() {
+        let mut attributes = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(".gitattributes")
+            .map_err(|e| format!("Failed to open .gitattributes: {:?}", e))?;
+        for pattern in patterns {
+            writeln!(attributes, "{} filter={}", pattern, FILTER)
+                .map_err(|e| format!("Failed to write .gitattributes: {:?}", e))?;
+        }
+        println!("Encrypting {} in .gitattributes", patterns.join(", "));
+    }
+
+    Ok(())
+}
+
+// Entry point for `lockbox git-filter clean|smudge`, streaming stdin to stdout
+pub fn filter(mode: Option<&str>) -> Result<(), String> {
+    let mut input = Vec::new();
+    io::stdin()
+        .read_to_end(&mut input)
+        .map_err(|e| format!("Failed to read file from git: {:?}", e))?;
+
+    let key_dir = git_dir()?.join(FILTER);
+    let output = match mode {
+        Some("clean") => clean(&key_dir, &input)?,
+        Some("smudge") => smudge(&key_dir, &input)?,
+        _ => return Err("Usage: lockbox git-filter clean|smudge".to_string()),
+    };
+
+    io::stdout()
+        .write_all(&output)
+        .map_err(|e| format!("Failed to write file to git: {:?}", e))
+}
+
+// Encrypt plaintext for the index, reusing earlier ciphertext for unchanged content
+fn clean(key_dir: &Path, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    if plaintext.starts_with(AGE_HEADER) {
+        return Ok(plaintext.to_vec());
+    }
+
+    // age output is randomised, so without the cache every status would show a diff
+    let cached = cache_path(key_dir, plaintext);
+    if let Ok(ciphertext) = fs::read(&cached) {
+        return Ok(ciphertext);
+    }
+
+    let identity = load_identity(key_dir)?;
+    let recipient = identity.to_public().to_string();
+    let ciphertext = encrypt_to_recipients(plaintext, &[recipient])?;
+    fs::write(&cached, &ciphertext).map_err(|e| format!("Failed to cache ciphertext: {:?}", e))?;
+    Ok(ciphertext)
+}
+
+// Decrypt ciphertext from the index into the working tree
+fn smudge(key_dir: &Path, ciphertext: &[u8]) -> Result<Vec<u8>, String> {
+    if !ciphertext.starts_with(AGE_HEADER) {
+        return Ok(ciphertext.to_vec());
+    }
+
+    // Without the key the ciphertext is checked out as is
+    let identity = match load_identity(key_dir) {
+        Ok(identity) => identity,
+        Err(e) => {
+            eprintln!("lockbox: {}, leaving file encrypted", e);
+            return Ok(ciphertext.to_vec());
+        }
+    };
+
+    let plaintext = decrypt_with_identity(ciphertext, &identity)?;
+    // Remember the pairing so cleaning the checkout reproduces the same blob
+    fs::write(cache_path(key_dir, &plaintext), ciphertext)
+        .map_err(|e| format!("Failed to cache ciphertext: {:?}", e))?;
+    Ok(plaintext)
+}
+
+fn load_identity(key_dir: &Path) -> Result<x25519::Identity, String> {
+    let identity = fs::read_to_string(key_dir.join("identity"))
+        .map_err(|_| "No repository key, run `lockbox git init` first".to_string())?;
+    identity
+        .trim()
+        .parse()
+        .map_err(|e| format!("Repository key is corrupted: {}", e))
+}
+
+fn cache_path(key_dir: &Path, plaintext: &[u8]) -> PathBuf {
+    key_dir
+        .join("cache")
+        .join(hex::encode(Sha256::digest(plaintext)))
+}
+
+fn git_dir() -> Result<PathBuf, String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--git-dir"])
+        .output()
+        .map_err(|e| format!("Failed to run git: {:?}", e))?;
+    if !output.status.success() {
+        return Err("Not inside a git repository".to_string());
+    }
+    Ok(PathBuf::from(
+        String::from_utf8_lossy(&output.stdout).trim(),
+    ))
+}
+
+fn git_config(key: &str, value: &str) -> Result<(), String> {
+    let status = Command::new("git")
+        .args(["config", &format!("filter.{}.{}", FILTER, key), value])
+        .status()
+        .map_err(|e| format!("Failed to run git: {:?}", e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("Failed to set filter.{}.{}", FILTER, key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A key directory like `lockbox git init` leaves in .git
+    fn key_dir() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("cache")).unwrap();
+        let identity = x25519::Identity::generate();
+        fs::write(
+            dir.path().join("identity"),
+            identity.to_string().expose_secret(),
+        )
+        .unwrap();
+        dir
+    }
+
+    #[test]
+    fn clean_then_smudge_round_trips() {
+        let dir = key_dir();
+        let ciphertext = clean(dir.path(), b"API_KEY=secret\n").unwrap();
+        assert!(ciphertext.starts_with(AGE_HEADER));
+        assert_eq!(
+            smudge(dir.path(), &ciphertext).unwrap(),
+            b"API_KEY=secret\n"
+        );
+    }
+
+    #[test]
+    fn clean_is_deterministic() {
+        let dir = key_dir();
+        let first = clean(dir.path(), b"same content").unwrap();
+        assert_eq!(clean(dir.path(), b"same content").unwrap(), first);
+        assert_ne!(clean(dir.path(), b"other content").unwrap(), first);
+    }
+
+    #[test]
+    fn smudged_checkout_cleans_to_the_committed_blob() {
+        // A fresh clone has an empty cache, so the blob comes from another machine
+        let dir = key_dir();
+        let committed = clean(dir.path(), b"shared").unwrap();
+        fs::remove_dir_all(dir.path().join("cache")).unwrap();
+        fs::create_dir(dir.path().join("cache")).unwrap();
+
+        let plaintext = smudge(dir.path(), &committed).unwrap();
+        assert_eq!(clean(dir.path(), &plaintext).unwrap(), committed);
+    }
+
+    #[test]
+    fn encrypted_input_passes_through_clean() {
+        let dir = key_dir();
+        let ciphertext = clean(dir.path(), b"once").unwrap();
+        fs::remove_dir_all(dir.path().join("cache")).unwrap();
+        assert_eq!(clean(dir.path(), &ciphertext).unwrap(), ciphertext);
+    }
+
+    #[test]
+    fn smudge_without_a_key_leaves_the_file_encrypted() {
+        let dir = key_dir();
+        let ciphertext = clean(dir.path(), b"hidden").unwrap();
+        fs::remove_file(dir.path().join("identity")).unwrap();
+        assert_eq!(smudge(dir.path(), &ciphertext).unwrap(), ciphertext);
+        assert_eq!(smudge(dir.path(), b"plain").unwrap(), b"plain");
+    }
+}
@@ -0,0 +1,301 @@
+// src/history.rs
+
+use crate::session;
+use crate::store::{AlternateRowDark, BlueButton, GreenButton, OrangeButton};
+use chrono::{DateTime, Local};
+use iced::{
+    alignment, button, scrollable, Alignment, Button, Color, Column, Container, Element, Image,
+    Length, Row, Scrollable, Text,
+};
+use serde::Serialize;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// Kinds of operation the store page and watch folder perform
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Kind {
+    Encrypt,
+    Decrypt,
+    EncryptText,
+    DecryptText,
+    Shred,
+    SaveWorkingCopy,
+    WatchEncrypt,
+}
+
+impl Kind {
+    // Past tense for success messages
+    fn done(self) -> &'static str {
+        match self {
+            Kind::Encrypt => "Encrypted",
+            Kind::Decrypt => "Decrypted",
+            Kind::EncryptText => "Encrypted text",
+            Kind::DecryptText => "Decrypted text",
+            Kind::Shred => "Shredded",
+            Kind::SaveWorkingCopy => "Saved",
+            Kind::WatchEncrypt => "Watch folder encrypted",
+        }
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Kind::Encrypt => "Encrypt",
+            Kind::Decrypt => "Decrypt",
+            Kind::EncryptText => "Encrypt text",
+            Kind::DecryptText => "Decrypt text",
+            Kind::Shred => "Shred",
+            Kind::SaveWorkingCopy => "Save working copy",
+            Kind::WatchEncrypt => "Watch folder",
+        })
+    }
+}
+
+// One finished operation, as listed and exported
+#[derive(Debug, Clone, Serialize)]
+pub struct Operation {
+    pub timestamp: DateTime<Local>,
+    pub kind: Kind,
+    pub path: Option<PathBuf>,
+    pub size: Option<u64>,
+    pub duration_ms: u64,
+    pub error: Option<String>,
+}
+
+impl Operation {
+    pub fn new<T>(
+        kind: Kind,
+        path: Option<&Path>,
+        size: Option<u64>,
+        duration: Duration,
+        result: &Result<T, String>,
+    ) -> Self {
+        Self {
+            timestamp: Local::now(),
+            kind,
+            path: path.map(Path::to_path_buf),
+            size,
+            duration_ms: duration.as_millis() as u64,
+            error: result.as_ref().err().cloned(),
+        }
+    }
+
+    pub fn failed(&self) -> bool {
+        self.error.is_some()
+    }
+
+    fn file_name(&self) -> Option<String> {
+        self.path.as_ref().map(|path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.to_string_lossy().to_string())
+        })
+    }
+
+    // One line for a toast
+    pub fn summary(&self) -> String {
+        match (&self.error, self.file_name()) {
+            (None, Some(name)) => format!("{} {}", self.kind.done(), name),
+            (None, None) => self.kind.done().to_string(),
+            (Some(e), Some(name)) => format!("{} failed for {}: {}", self.kind, name, e),
+            (Some(e), None) => format!("{} failed: {}", self.kind, e),
+        }
+    }
+}
+
+// Formats the history can be exported in
+#[derive(Debug, Clone, Copy)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+// Define the state of the history page, listing this session's operations
+#[derive(Default)]
+pub struct HistoryPage {
+    operations: Vec<Operation>,
+    status: Option<Result<String, String>>,
+    scroll: scrollable::State,
+    json_button: button::State,
+    csv_button: button::State,
+    clear_button: button::State,
+    back_button: button::State,
+}
+
+impl HistoryPage {
+    pub fn push(&mut self, operation: Operation) {
+        self.operations.push(operation);
+    }
+
+    pub fn clear(&mut self) {
+        self.operations.clear();
+        self.status = None;
+    }
+
+    pub fn export(&mut self, format: ExportFormat) {
+        let (name, extension) = match format {
+            ExportFormat::Json => ("JSON", "json"),
+            ExportFormat::Csv => ("CSV", "csv"),
+        };
+        let destination = match session::output_dialog()
+            .add_filter(name, &[extension])
+            .set_file_name(&format!("lockbox-history.{}", extension))
+            .save_file()
+        {
+            Some(destination) => destination,
+            None => return,
+        };
+        session::remember_output(&destination);
+
+        let contents = match format {
+            ExportFormat::Json => serde_json::to_string_pretty(&self.operations)
+                .map_err(|e| format!("Failed to serialize history: {:?}", e)),
+            ExportFormat::Csv => self.to_csv(),
+        };
+        self.status = Some(
+            contents
+                .and_then(|contents| {
+                    fs::write(&destination, contents)
+                        .map_err(|e| format!("Failed to write history: {:?}", e))
+                })
+                .map(|_| {
+                    format!(
+                        "Exported {} operations to {}",
+                        self.operations.len(),
+                        destination.display()
+                    )
+                }),
+        );
+    }
+
+    fn to_csv(&self) -> Result<String, String> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        for operation in &self.operations {
+            writer
+                .serialize(operation)
+                .map_err(|e| format!("Failed to write CSV: {:?}", e))?;
+        }
+        let bytes = writer
+            .into_inner()
+            .map_err(|e| format!("Failed to write CSV: {:?}", e))?;
+        String::from_utf8(bytes).map_err(|e| format!("Failed to write CSV: {:?}", e))
+    }
+
+    pub fn view(&mut self) -> Element<'_, crate::Message> {
+        let logo = Container::new(
+            Image::new("images/logo.png")
+                .width(Length::Units(40))
+                .height(Length::Units(40)),
+        )
+        .padding(10)
+        .align_x(alignment::Horizontal::Center);
+
+        let cell = |text: String, width: u16| Text::new(text).size(14).width(Length::Units(width));
+
+        let header = Row::new()
+            .spacing(10)
+            .push(cell("Time".to_string(), 70))
+            .push(cell("Action".to_string(), 120))
+            .push(cell("File".to_string(), 160))
+            .push(cell("Size".to_string(), 80))
+            .push(cell("Duration".to_string(), 70))
+            .push(cell("Result".to_string(), 200));
+
+        let mut rows = Scrollable::new(&mut self.scroll)
+            .spacing(5)
+            .height(Length::Units(320));
+        if self.operations.is_empty() {
+            rows = rows.push(
+                Text::new("Nothing has been encrypted or decrypted since you logged in.")
+                    .size(14)
+                    .color(Color::from_rgb(0.5, 0.5, 0.5)),
+            );
+        }
+        // Newest first
+        for operation in self.operations.iter().rev() {
+            let result = match &operation.error {
+                Some(e) => Text::new(e.as_str()).color(Color::from_rgb(0.8, 0.2, 0.2)),
+                None => Text::new("OK").color(Color::from_rgb(0.3, 0.6, 0.4)),
+            };
+            rows = rows.push(
+                Row::new()
+                    .spacing(10)
+                    .push(cell(operation.timestamp.format("%H:%M:%S").to_string(), 70))
+                    .push(cell(operation.kind.to_string(), 120))
+                    .push(cell(operation.file_name().unwrap_or_default(), 160))
+                    .push(cell(
+                        operation.size.map(format_size).unwrap_or_default(),
+                        80,
+                    ))
+                    .push(cell(format!("{} ms", operation.duration_ms), 70))
+                    .push(result.size(14).width(Length::Units(200))),
+            );
+        }
+
+        let mut content = Column::new()
+            .spacing(20)
+            .align_items(Alignment::Center)
+            .push(Text::new("History").size(24))
+            .push(
+                Container::new(Column::new().spacing(10).push(header).push(rows))
+                    .style(AlternateRowDark)
+                    .padding(10)
+                    .width(Length::Units(760)),
+            );
+
+        if let Some(status) = &self.status {
+            let (message, color) = match status {
+                Ok(message) => (message, Color::from_rgb(0.3, 0.6, 0.4)),
+                Err(error) => (error, Color::from_rgb(0.8, 0.2, 0.2)),
+            };
+            content = content.push(Text::new(message).size(14).color(color));
+        }
+
+        content = content.push(
+            Row::new()
+                .spacing(10)
+                .push(
+                    Button::new(&mut self.json_button, Text::new("Export JSON").size(16))
+                        .style(GreenButton)
+                        .on_press(crate::Message::ExportHistory(ExportFormat::Json)),
+                )
+                .push(
+                    Button::new(&mut self.csv_button, Text::new("Export CSV").size(16))
+                        .style(GreenButton)
+                        .on_press(crate::Message::ExportHistory(ExportFormat::Csv)),
+                )
+                .push(
+                    Button::new(&mut self.clear_button, Text::new("Clear").size(16))
+                        .style(OrangeButton)
+                        .on_press(crate::Message::ClearHistory),
+                )
+                .push(
+                    Button::new(&mut self.back_button, Text::new("Back").size(16))
+                        .style(BlueButton)
+                        .on_press(crate::Message::SwitchToStorePage),
+                ),
+        );
+
+        let container = Container::new(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y();
+
+        Column::new().push(logo).push(container).into()
+    }
+}
+
+// Human-readable file size
+pub fn format_size(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{} bytes", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{:.2} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{:.2} MB", bytes as f64 / (1024.0 * 1024.0))
+    }
+}
@@ -0,0 +1,470 @@
+// src/import.rs
+
+use crate::passwords::{CustomField, Entry};
+use crate::session;
+use crate::store::{AlternateRowDark, BlueButton, GreenButton};
+use iced::{
+    button, scrollable, text_input, Alignment, Button, Checkbox, Color, Column, Container, Element,
+    Length, Row, Scrollable, Text, TextInput,
+};
+use keepass::db::fields;
+use keepass::{Database, DatabaseKey};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+// Formats lockbox can import credentials from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    Kdbx,
+    Csv,
+}
+
+// What importing a single entry would do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportAction {
+    Add,
+    Conflict(u64),
+    Duplicate,
+}
+
+// An entry read from the source together with its planned action
+pub struct ImportCandidate {
+    pub entry: Entry,
+    pub action: ImportAction,
+}
+
+// Read all entries of a KeePass database
+pub fn read_kdbx(
+    path: &Path,
+    password: &str,
+    keyfile: Option<&Path>,
+) -> Result<Vec<Entry>, String> {
+    let mut key = DatabaseKey::new();
+    if !password.is_empty() {
+        key = key.with_password(password);
+    }
+    if let Some(keyfile) = keyfile {
+        let mut keyfile =
+            File::open(keyfile).map_err(|e| format!("Failed to open key file: {:?}", e))?;
+        key = key
+            .with_keyfile(&mut keyfile)
+            .map_err(|e| format!("Failed to read key file: {:?}", e))?;
+    }
+
+    let mut file = File::open(path).map_err(|e| format!("Failed to open database: {:?}", e))?;
+    let database =
+        Database::open(&mut file, key).map_err(|e| format!("Failed to open database: {}", e))?;
+
+    let entries = database
+        .iter_all_entries()
+        .map(|kdbx_entry| {
+            let get = |name: &str| kdbx_entry.get(name).unwrap_or_default().to_string();
+            let mut custom_fields: Vec<CustomField> = kdbx_entry
+                .fields
+                .keys()
+                .filter(|name| !fields::KNOWN_FIELDS.contains(&name.as_str()))
+                .map(|name| CustomField {
+                    name: name.clone(),
+                    value: get(name),
+                })
+                .collect();
+            custom_fields.sort_by(|a, b| a.name.cmp(&b.name));
+
+            Entry {
+                site: get(fields::TITLE),
+                username: get(fields::USERNAME),
+                password: get(fields::PASSWORD),
+                url: get(fields::URL),
+                notes: get(fields::NOTES),
+                custom_fields,
+                ..Entry::default()
+            }
+        })
+        .collect();
+
+    Ok(entries)
+}
+
+// Read a CSV export from a browser or password manager
+pub fn read_csv(path: &Path) -> Result<Vec<Entry>, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_path(path)
+        .map_err(|e| format!("Failed to open CSV file: {}", e))?;
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| format!("Failed to read CSV header: {}", e))?
+        .iter()
+        .map(|header| header.trim().to_lowercase())
+        .collect();
+
+    let mut entries = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| format!("Failed to read CSV row: {}", e))?;
+        let mut entry = Entry::default();
+
+        for (header, value) in headers.iter().zip(record.iter()) {
+            if value.is_empty() {
+                continue;
+            }
+
+            // Column names used by common browsers and password managers
+            let target = match header.as_str() {
+                "name" | "title" | "account" => &mut entry.site,
+                "username" | "login_username" | "login name" | "login" => &mut entry.username,
+                "password" | "login_password" => &mut entry.password,
+                "url" | "login_uri" | "web site" | "website" => &mut entry.url,
+                "notes" | "note" | "extra" | "comments" => &mut entry.notes,
+                _ => {
+                    entry.custom_fields.push(CustomField {
+                        name: header.clone(),
+                        value: value.to_string(),
+                    });
+                    continue;
+                }
+            };
+            *target = value.to_string();
+        }
+
+        // Browser exports have no title, so fall back to the host
+        if entry.site.is_empty() {
+            entry.site = host(&entry.url);
+        }
+        entries.push(entry);
+    }
+
+    Ok(entries)
+}
+
+// Decide for every incoming entry whether it is new or clashes with existing data
+pub fn plan(existing: &[Entry], incoming: Vec<Entry>) -> Vec<ImportCandidate> {
+    let mut candidates: Vec<ImportCandidate> = Vec::new();
+    for entry in incoming {
+        let action = if candidates.iter().any(|c| same_login(&c.entry, &entry)) {
+            ImportAction::Duplicate
+        } else if let Some(current) = existing.iter().find(|e| same_login(e, &entry)) {
+            ImportAction::Conflict(current.id)
+        } else {
+            ImportAction::Add
+        };
+        candidates.push(ImportCandidate { entry, action });
+    }
+    candidates
+}
+
+fn same_login(a: &Entry, b: &Entry) -> bool {
+    a.site.eq_ignore_ascii_case(&b.site) && a.username == b.username
+}
+
+// Host part of a URL, or the URL itself if it cannot be parsed
+fn host(url: &str) -> String {
+    let without_scheme = url.split("://").nth(1).unwrap_or(url);
+    without_scheme
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+// Define the state of the import panel on the password vault page
+#[derive(Default)]
+pub struct ImportPanel {
+    format: Option<ImportFormat>,
+    source: Option<PathBuf>,
+    keyfile: Option<PathBuf>,
+    password: String,
+    candidates: Vec<ImportCandidate>,
+    overwrite_conflicts: bool,
+    error: Option<String>,
+    scroll: scrollable::State,
+    password_input: text_input::State,
+    kdbx_button: button::State,
+    csv_button: button::State,
+    keyfile_button: button::State,
+    preview_button: button::State,
+    import_button: button::State,
+}
+
+impl ImportPanel {
+    pub fn choose_source(&mut self, format: ImportFormat) {
+        let dialog = match format {
+            ImportFormat::Kdbx => session::open_dialog().add_filter("KeePass", &["kdbx"]),
+            ImportFormat::Csv => session::open_dialog().add_filter("CSV", &["csv"]),
+        };
+        if let Some(path) = dialog.pick_file() {
+            session::remember_open(&path);
+            *self = Self {
+                format: Some(format),
+                source: Some(path),
+                ..Self::default()
+            };
+        }
+    }
+
+    pub fn choose_keyfile(&mut self) {
+        self.keyfile = session::open_dialog().pick_file();
+        if let Some(path) = &self.keyfile {
+            session::remember_open(path);
+        }
+    }
+
+    pub fn password_changed(&mut self, value: String) {
+        self.password = value;
+    }
+
+    pub fn overwrite_toggled(&mut self, overwrite: bool) {
+        self.overwrite_conflicts = overwrite;
+    }
+
+    // Dry run: read the source and list what would happen without touching the vault
+    pub fn preview(&mut self, existing: &[Entry]) {
+        let source = match &self.source {
+            Some(source) => source,
+            None => return,
+        };
+
+        let incoming = match self.format {
+            Some(ImportFormat::Kdbx) => read_kdbx(source, &self.password, self.keyfile.as_deref()),
+            Some(ImportFormat::Csv) => read_csv(source),
+            None => return,
+        };
+
+        match incoming {
+            Ok(incoming) => {
+                self.candidates = plan(existing, incoming);
+                self.error = None;
+            }
+            Err(e) => {
+                self.candidates.clear();
+                self.error = Some(e);
+            }
+        }
+    }
+
+    // Take the previewed entries that should be written, leaving the panel empty
+    pub fn take_accepted(&mut self) -> Vec<ImportCandidate> {
+        let overwrite = self.overwrite_conflicts;
+        let candidates = std::mem::take(&mut self.candidates);
+        *self = Self::default();
+        candidates
+            .into_iter()
+            .filter(|candidate| match candidate.action {
+                ImportAction::Add => true,
+                ImportAction::Conflict(_) => overwrite,
+                ImportAction::Duplicate => false,
+            })
+            .collect()
+    }
+
+    pub fn view(&mut self) -> Element<'_, crate::Message> {
+        let source_row = Row::new()
+            .spacing(10)
+            .push(
+                Button::new(&mut self.kdbx_button, Text::new("KeePass").size(16))
+                    .style(BlueButton)
+                    .on_press(crate::Message::ChooseImportSource(ImportFormat::Kdbx)),
+            )
+            .push(
+                Button::new(&mut self.csv_button, Text::new("CSV").size(16))
+                    .style(BlueButton)
+                    .on_press(crate::Message::ChooseImportSource(ImportFormat::Csv)),
+            );
+
+        let mut panel = Column::new()
+            .spacing(10)
+            .width(Length::Units(260))
+            .push(Text::new("Import").size(20))
+            .push(source_row);
+
+        if let Some(source) = &self.source {
+            panel = panel.push(Text::new(source.to_string_lossy()).size(14));
+        }
+
+        if self.format == Some(ImportFormat::Kdbx) {
+            let keyfile_label = match &self.keyfile {
+                Some(keyfile) => keyfile
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                None => "Key File".to_string(),
+            };
+            panel = panel
+                .push(
+                    TextInput::new(
+                        &mut self.password_input,
+                        "master password",
+                        &self.password,
+                        crate::Message::ImportPasswordChanged,
+                    )
+                    .padding(8)
+                    .size(16)
+                    .password(),
+                )
+                .push(
+                    Button::new(&mut self.keyfile_button, Text::new(keyfile_label).size(16))
+                        .style(BlueButton)
+                        .on_press(crate::Message::ChooseImportKeyfile),
+                );
+        }
+
+        if self.source.is_some() {
+            panel = panel.push(
+                Button::new(&mut self.preview_button, Text::new("Preview").size(16))
+                    .style(BlueButton)
+                    .on_press(crate::Message::PreviewImport),
+            );
+        }
+
+        if let Some(error) = &self.error {
+            panel = panel.push(
+                Text::new(error)
+                    .size(14)
+                    .color(Color::from_rgb(0.8, 0.2, 0.2)),
+            );
+        }
+
+        if !self.candidates.is_empty() {
+            let count = |wanted: fn(&ImportAction) -> bool| {
+                self.candidates
+                    .iter()
+                    .filter(|candidate| wanted(&candidate.action))
+                    .count()
+            };
+            let summary = format!(
+                "{} new, {} conflicts, {} duplicates",
+                count(|action| *action == ImportAction::Add),
+                count(|action| matches!(action, ImportAction::Conflict(_))),
+                count(|action| *action == ImportAction::Duplicate),
+            );
+
+            let mut list = Scrollable::new(&mut self.scroll)
+                .spacing(5)
+                .height(Length::Units(150));
+            for candidate in &self.candidates {
+                let (label, color) = match candidate.action {
+                    ImportAction::Add => ("new", Color::from_rgb(0.3, 0.6, 0.4)),
+                    ImportAction::Conflict(_) => ("conflict", Color::from_rgb(0.86, 0.59, 0.26)),
+                    ImportAction::Duplicate => ("duplicate", Color::from_rgb(0.5, 0.5, 0.5)),
+                };
+                list = list.push(
+                    Row::new()
+                        .spacing(10)
+                        .align_items(Alignment::Center)
+                        .push(Text::new(label).size(14).color(color))
+                        .push(
+                            Text::new(format!(
+                                "{} - {}",
+                                candidate.entry.site, candidate.entry.username
+                            ))
+                            .size(14),
+                        ),
+                );
+            }
+
+            panel = panel
+                .push(Text::new(summary).size(14))
+                .push(Container::new(list).style(AlternateRowDark).padding(5))
+                .push(
+                    Checkbox::new(
+                        self.overwrite_conflicts,
+                        "Overwrite conflicts",
+                        crate::Message::ToggleOverwriteConflicts,
+                    )
+                    .size(16),
+                )
+                .push(
+                    Button::new(&mut self.import_button, Text::new("Import").size(16))
+                        .style(GreenButton)
+                        .on_press(crate::Message::ConfirmImport),
+                );
+        }
+
+        panel.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn csv(contents: &str) -> Vec<Entry> {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+        read_csv(file.path()).unwrap()
+    }
+
+    fn entry(site: &str, username: &str) -> Entry {
+        Entry {
+            site: site.to_string(),
+            username: username.to_string(),
+            ..Entry::default()
+        }
+    }
+
+    #[test]
+    fn bitwarden_headers_map_to_fields() {
+        let entries = csv(
+            "name,login_username,login_password,login_uri,notes,folder\n\
+             Mail,alice,hunter2,https://mail.example.com,personal,Work\n",
+        );
+        assert_eq!(entries.len(), 1);
+        let entry = &entries[0];
+        assert_eq!(entry.site, "Mail");
+        assert_eq!(entry.username, "alice");
+        assert_eq!(entry.password, "hunter2");
+        assert_eq!(entry.url, "https://mail.example.com");
+        assert_eq!(entry.notes, "personal");
+        assert_eq!(entry.custom_fields.len(), 1);
+        assert_eq!(entry.custom_fields[0].name, "folder");
+        assert_eq!(entry.custom_fields[0].value, "Work");
+    }
+
+    #[test]
+    fn headers_are_trimmed_and_case_insensitive() {
+        let entries = csv(" Title ,Login Name,Password,Web Site\nBank,bob,pw,bank.example\n");
+        assert_eq!(entries[0].site, "Bank");
+        assert_eq!(entries[0].username, "bob");
+        assert_eq!(entries[0].url, "bank.example");
+        assert!(entries[0].custom_fields.is_empty());
+    }
+
+    #[test]
+    fn browser_export_falls_back_to_the_host() {
+        let entries = csv("url,username,password\n\
+             https://shop.example.com/login?next=/,carol,secret\n");
+        assert_eq!(entries[0].site, "shop.example.com");
+    }
+
+    #[test]
+    fn empty_cells_are_skipped() {
+        let entries = csv("name,username,password,extra\nSite,dave,pw,\n");
+        assert_eq!(entries[0].notes, "");
+        assert!(entries[0].custom_fields.is_empty());
+    }
+
+    #[test]
+    fn plan_marks_conflicts_and_duplicates() {
+        let mut current = entry("Mail", "alice");
+        current.id = 7;
+        let candidates = plan(
+            &[current],
+            vec![
+                entry("mail", "alice"),
+                entry("Bank", "alice"),
+                entry("BANK", "alice"),
+                entry("Bank", "bob"),
+            ],
+        );
+        let actions: Vec<ImportAction> = candidates.iter().map(|c| c.action).collect();
+        assert_eq!(
+            actions,
+            vec![
+                ImportAction::Conflict(7),
+                ImportAction::Add,
+                ImportAction::Duplicate,
+                ImportAction::Add,
+            ]
+        );
+    }
+}
@@ -0,0 +1,208 @@
+// src/kit.rs
+
+use crate::vault::Vault;
+use age::secrecy::{ExposeSecret, SecretString};
+use bech32::{FromBase32, ToBase32, Variant};
+use bip39::Mnemonic;
+use qrcode::{Color, QrCode};
+use std::fs;
+use std::path::Path;
+
+// Human readable part of an age identity
+const IDENTITY_HRP: &str = "age-secret-key-";
+
+// Page layout of the printed kit, in SVG user units
+const PAGE_WIDTH: usize = 595;
+const PAGE_HEIGHT: usize = 842;
+const MARGIN: usize = 50;
+
+// Encode the vault identity as 24 checksummed words
+pub fn identity_words(secret: &SecretString) -> Result<String, String> {
+    let (hrp, data, _) = bech32::decode(secret.expose_secret())
+        .map_err(|e| format!("Failed to decode identity: {:?}", e))?;
+    if hrp != IDENTITY_HRP {
+        return Err("Vault identity is not an age identity".to_string());
+    }
+    let key =
+        Vec::<u8>::from_base32(&data).map_err(|e| format!("Failed to decode identity: {:?}", e))?;
+    let mnemonic =
+        Mnemonic::from_entropy(&key).map_err(|e| format!("Failed to encode identity: {:?}", e))?;
+    Ok(mnemonic.to_string())
+}
+
+// Turn typed-in words back into an age identity, validating the checksum
+pub fn words_identity(words: &str) -> Result<String, String> {
+    let words = words.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mnemonic = Mnemonic::parse_normalized(&words.to_lowercase()).map_err(|e| match e {
+        bip39::Error::InvalidChecksum => "The words do not match their checksum".to_string(),
+        bip39::Error::UnknownWord(index) => format!("Word {} is not in the word list", index + 1),
+        bip39::Error::BadWordCount(count) => format!("Expected 24 words, got {}", count),
+        e => format!("Invalid recovery words: {}", e),
+    })?;
+    let identity = bech32::encode(
+        IDENTITY_HRP,
+        mnemonic.to_entropy().to_base32(),
+        Variant::Bech32,
+    )
+    .map_err(|e| format!("Failed to encode identity: {:?}", e))?;
+    Ok(identity.to_uppercase())
+}
+
+// Render the printable recovery kit as an SVG document
+pub fn render(username: &str, words: &str) -> Result<String, String> {
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" \
+         viewBox=\"0 0 {w} {h}\" font-family=\"Helvetica, Arial, sans-serif\">\n\
+         <rect width=\"{w}\" height=\"{h}\" fill=\"white\"/>\n",
+        w = PAGE_WIDTH,
+        h = PAGE_HEIGHT
+    );
+
+    let mut text = |x: usize, y: usize, size: usize, weight: &str, content: &str| {
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" font-size=\"{}\" font-weight=\"{}\">{}</text>\n",
+            x,
+            y,
+            size,
+            weight,
+            escape(content)
+        ));
+    };
+
+    text(MARGIN, 80, 28, "bold", "lockbox recovery kit");
+    text(
+        MARGIN,
+        115,
+        14,
+        "normal",
+        &format!("Username: {}", username),
+    );
+    text(
+        MARGIN,
+        135,
+        14,
+        "normal",
+        "Keep this page somewhere safe and offline.",
+    );
+
+    // Recovery words in three numbered columns
+    text(MARGIN, 185, 16, "bold", "Recovery words");
+    for (index, word) in words.split_whitespace().enumerate() {
+        let x = MARGIN + (index / 8) * 170;
+        let y = 215 + (index % 8) * 24;
+        text(x, y, 14, "normal", &format!("{:>2}. {}", index + 1, word));
+    }
+
+    text(MARGIN, 445, 16, "bold", "To restore");
+    let steps = [
+        "1. Install lockbox and open the login screen.",
+        "2. Choose \"recover account\" and switch to recovery words.",
+        "3. Enter the username above and type the 24 words.",
+        "4. Choose a new password. Your vault opens with it from now on.",
+    ];
+    for (index, step) in steps.iter().enumerate() {
+        text(MARGIN, 475 + index * 22, 13, "normal", step);
+    }
+
+    // QR code of the words, drawn as one rect per dark module
+    let code =
+        QrCode::new(words.as_bytes()).map_err(|e| format!("Failed to create QR code: {:?}", e))?;
+    let width = code.width();
+    let module = 180 / width.max(1);
+    let (left, top) = (PAGE_WIDTH - MARGIN - width * module, 580);
+    text(
+        MARGIN,
+        600,
+        13,
+        "normal",
+        "The code holds the same 24 words.",
+    );
+    for (index, color) in code.to_colors().iter().enumerate() {
+        if *color == Color::Dark {
+            svg.push_str(&format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{m}\" height=\"{m}\" fill=\"black\"/>\n",
+                left + (index % width) * module,
+                top + (index / width) * module,
+                m = module
+            ));
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    Ok(svg)
+}
+
+// Write the kit for the unlocked vault to `dest`
+pub fn export(username: &str, vault: &Vault, dest: &Path) -> Result<(), String> {
+    let words = identity_words(&vault.identity_secret())?;
+    let svg = render(username, &words)?;
+    fs::write(dest, svg).map_err(|e| format!("Failed to write recovery kit: {:?}", e))
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use age::x25519;
+
+    #[test]
+    fn words_round_trip_to_the_identity() {
+        let secret = x25519::Identity::generate().to_string();
+        let words = identity_words(&secret).unwrap();
+        assert_eq!(words.split_whitespace().count(), 24);
+        assert_eq!(words_identity(&words).unwrap(), *secret.expose_secret());
+    }
+
+    #[test]
+    fn typed_words_are_normalized() {
+        let secret = x25519::Identity::generate().to_string();
+        let words = identity_words(&secret).unwrap();
+        let typed = format!("  {}\n", words.to_uppercase().replace(' ', "   "));
+        assert_eq!(words_identity(&typed).unwrap(), *secret.expose_secret());
+    }
+
+    #[test]
+    fn checksum_and_word_errors_are_reported() {
+        let secret = x25519::Identity::generate().to_string();
+        let mut words: Vec<String> = identity_words(&secret)
+            .unwrap()
+            .split(' ')
+            .map(str::to_string)
+            .collect();
+
+        let short = words[..23].join(" ");
+        assert_eq!(
+            words_identity(&short).unwrap_err(),
+            "Expected 24 words, got 23"
+        );
+
+        words[3] = "notaword".to_string();
+        assert_eq!(
+            words_identity(&words.join(" ")).unwrap_err(),
+            "Word 4 is not in the word list"
+        );
+
+        // Every word is valid, but the last one carries the checksum of all-zero entropy
+        let zeros = "abandon ".repeat(23);
+        assert!(words_identity(&format!("{}art", zeros)).is_ok());
+        assert_eq!(
+            words_identity(&format!("{}abandon", zeros)).unwrap_err(),
+            "The words do not match their checksum"
+        );
+    }
+
+    #[test]
+    fn rendered_kit_escapes_the_username() {
+        let secret = x25519::Identity::generate().to_string();
+        let words = identity_words(&secret).unwrap();
+        let svg = render("<me & you>", &words).unwrap();
+        assert!(svg.contains("Username: &lt;me &amp; you&gt;"));
+        assert!(svg.contains(words.split(' ').next().unwrap()));
+    }
+}
@@ -1,25 +1,49 @@
 // src/login.rs
 
+use crate::backup::{BackupPage, RestoreForm};
+use crate::cipher::Backend;
+use crate::config;
+use crate::confirm::{self, Confirmation, Discard, Pending};
+use crate::envfile::EnvPage;
+use crate::generator::CharClass;
+use crate::history::{ExportFormat, HistoryPage};
+use crate::import::ImportFormat;
+use crate::notes::NotesPage;
+use crate::passwords::{EntryField, PasswordsPage};
+use crate::recovery::{RecoverForm, RecoveryPage};
+use crate::session;
+use crate::settings::SettingsPage;
+use crate::shortcuts::Shortcut;
+use crate::signup::SignupForm;
 use crate::store::StorePage;
+use crate::theme::ThemeSetting;
+use crate::toast::Toasts;
+use crate::vault::{self, Vault};
 use crate::AppState;
 use aes::Aes256;
 use block_modes::block_padding::Pkcs7;
 use block_modes::{BlockMode, Cbc};
 use dirs::config_dir;
-use dotenv::dotenv;
 use hex::{decode, encode};
-use iced::{button, text_input};
+use iced::{button, clipboard, text_input, Command};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
-use std::env;
+use std::collections::VecDeque;
+use std::fmt;
 use std::fs;
-use std::fs::OpenOptions;
-use std::io::{Read, Write};
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 // Create an alias for convenience
 type Aes256Cbc = Cbc<Aes256, Pkcs7>;
 
+// Failed logins allowed before the form locks
+const MAX_ATTEMPTS: u32 = 5;
+
+// First lockout after too many failures
+const LOCKOUT: Duration = Duration::from_secs(30);
+
 // Define the state of the login page
 #[derive(Default)]
 pub struct LoginPage {
@@ -28,9 +52,40 @@ pub struct LoginPage {
     pub username_input: text_input::State,
     pub password_input: text_input::State,
     pub login_button: button::State,
+    pub restore_button: button::State,
+    pub recover_button: button::State,
     pub authenticated: bool,
     pub state: AppState,
     pub store_page: StorePage, // Add this line
+    pub notes_page: NotesPage,
+    pub passwords_page: PasswordsPage,
+    pub backup_page: BackupPage,
+    pub env_page: EnvPage,
+    pub settings_page: SettingsPage,
+    pub history_page: HistoryPage,
+    pub toasts: Toasts,
+    // Questions waiting for an answer, the first one shown over the page
+    pub confirmations: VecDeque<Confirmation>,
+    pub restore_form: RestoreForm,
+    pub restoring: bool,
+    pub recovery_page: RecoveryPage,
+    pub recover_form: RecoverForm,
+    pub recovering: bool,
+    pub signup_button: button::State,
+    pub signup_form: SignupForm,
+    pub signing_up: bool,
+    pub logging_in: bool,
+    pub login_error: Option<LoginError>,
+    pub failed_attempts: u32,
+    pub locked_until: Option<Instant>,
+    pub spinner_frame: u32,
+    pub shift_held: bool,
+    pub caps_lock: bool,
+    pub last_activity: Option<Instant>,
+    pub exiting: bool,
+    pub vault: Option<Vault>,
+    pub clipboard_generation: u64,
+    pub clipboard_secret: Option<String>,
 }
 
 // Define the messages that can be sent in the application
@@ -39,10 +94,182 @@ pub enum Message {
     UsernameChanged(String),
     PasswordChanged(String),
     LoginPressed,
+    LoginFinished(Result<UnlockedVault, LoginError>),
+    SpinnerTick,
+    ModifiersChanged(bool),
+    CharacterTyped(char),
     SwitchToStorePage,
     TriggerFileSelection, // Add this line
     EncryptFile,
     DecryptFile,
+    CipherSelected(Backend),
+    FileHovered,
+    FileDropped(PathBuf),
+    ProcessDrop,
+    FilesHoveredLeft,
+    DismissDrop,
+    WindowResized(u32, u32),
+    WindowMoved(i32, i32),
+    CloseRequested,
+    PreviewFile,
+    ClosePreview,
+    OpenForEditing,
+    CheckWorkingCopy,
+    FinishEditing,
+    ToggleWatchPanel,
+    ToggleRecentPanel,
+    SelectRecentFile(PathBuf),
+    PinFile(PathBuf),
+    UnpinFile(PathBuf),
+    ClearRecentFiles,
+    ChooseWatchInput,
+    ChooseWatchOutput,
+    WatchRecipientsChanged(String),
+    ToggleWatchShred(bool),
+    ToggleWatching,
+    WatchedFilesChanged(Vec<PathBuf>),
+    WatchFailed(String),
+    ProcessWatchFolder,
+    ShredPassesChanged(u8),
+    ShredFile,
+    ToggleTextMode,
+    SnippetEdited(usize, String),
+    SnippetNewLine(usize),
+    PasteSnippet,
+    SnippetPasted(Option<String>),
+    EncryptText,
+    DecryptText,
+    ToggleRevealText,
+    CopyTextOutput,
+    SwitchToNotesPage,
+    NewNote,
+    SelectNote(u64),
+    DeleteNote,
+    NoteSearchChanged(String),
+    NoteTitleChanged(String),
+    NoteTagsChanged(String),
+    NoteBodyEdited(usize, String),
+    NoteBodyNewLine(usize),
+    PasteNoteBody,
+    NoteBodyPasted(Option<String>),
+    AutosaveNotes,
+    SwitchToPasswordsPage,
+    NewEntry,
+    SelectEntry(u64),
+    DeleteEntry,
+    EntrySearchChanged(String),
+    EntryFieldChanged(EntryField, String),
+    AddCustomField,
+    RemoveCustomField(usize),
+    CustomFieldNameChanged(usize, String),
+    CustomFieldValueChanged(usize, String),
+    ToggleRevealPassword,
+    CopyEntryField(EntryField),
+    ClearClipboard(u64),
+    ClipboardChecked(bool),
+    ToggleGeneratorMode,
+    GeneratorLengthChanged(u8),
+    GeneratorClassToggled(CharClass, bool),
+    GeneratePassword,
+    UseGeneratedPassword,
+    AutosavePasswords,
+    ToggleImportPanel,
+    ChooseImportSource(ImportFormat),
+    ChooseImportKeyfile,
+    ImportPasswordChanged(String),
+    PreviewImport,
+    ToggleOverwriteConflicts(bool),
+    ConfirmImport,
+    SwitchToBackupPage,
+    BackupPassphraseChanged(String),
+    BackupConfirmChanged(String),
+    ExportBackup,
+    ToggleRestoreForm,
+    ChooseRestoreSource,
+    RestorePassphraseChanged(String),
+    ToggleRestoreReplace(bool),
+    RestoreBackup,
+    SwitchToRecoveryPage,
+    RecoveryThresholdChanged(u8),
+    RecoverySharesChanged(u8),
+    CreateRecoveryShares,
+    CopyRecoveryShare(usize),
+    SaveRecoveryShares,
+    CreateRecoveryKit,
+    ToggleRecoverForm,
+    RecoverUsernameChanged(String),
+    RecoverShareChanged(usize, String),
+    AddRecoverShare,
+    ToggleRecoverMethod,
+    RecoverWordsChanged(String),
+    RecoverPasswordChanged(String),
+    RecoverConfirmChanged(String),
+    RecoverWithShares,
+    SwitchToEnvPage,
+    NewEnvFile,
+    OpenEnvFile,
+    SaveEnvFile,
+    AddEnvVar,
+    RemoveEnvVar(usize),
+    EnvKeyChanged(usize, String),
+    EnvValueChanged(usize, String),
+    ToggleRevealEnv,
+    SwitchToSettingsPage,
+    SwitchToHistoryPage,
+    ExportHistory(ExportFormat),
+    ClearHistory,
+    ExpireToasts,
+    DismissToast(u64),
+    ConfirmAction,
+    CancelAction,
+    ToggleDontAsk(bool),
+    ToggleConfirmation(confirm::Action, bool),
+    DeleteAccount,
+    ThemeSelected(ThemeSetting),
+    OutputDirectoryChanged(String),
+    ChooseOutputDirectory,
+    ToggleKeepOriginals(bool),
+    ToggleArmorOutput(bool),
+    ScryptWorkFactorChanged(String),
+    AutoLockChanged(String),
+    MinPasswordScoreChanged(u8),
+    WindowWidthChanged(String),
+    WindowHeightChanged(String),
+    SaveSettings,
+    ResetSettings,
+    CloseSettings,
+    AutoLockTick,
+    KeyboardShortcut(Shortcut),
+    ToggleSignupForm,
+    SignupUsernameChanged(String),
+    SignupPasswordChanged(String),
+    SignupConfirmChanged(String),
+    ToggleRevealSignup,
+    SignupPressed,
+}
+
+impl Message {
+    // Messages sent by timers and watchers rather than the user
+    fn is_background(&self) -> bool {
+        matches!(
+            self,
+            Message::LoginFinished(_)
+                | Message::SpinnerTick
+                | Message::AutoLockTick
+                | Message::ExpireToasts
+                | Message::CheckWorkingCopy
+                | Message::WatchedFilesChanged(_)
+                | Message::WatchFailed(_)
+                | Message::ProcessWatchFolder
+                | Message::ProcessDrop
+                | Message::AutosaveNotes
+                | Message::AutosavePasswords
+                | Message::ClearClipboard(_)
+                | Message::ClipboardChecked(_)
+                | Message::WindowResized(..)
+                | Message::WindowMoved(..)
+        )
+    }
 }
 
 // Define the authentication data structure
@@ -52,8 +279,151 @@ struct AuthData {
     password: String,
 }
 
+// Overwrite the account record with new credentials, used by recovery flows
+pub fn reset_credentials(username: &str, password: &str) -> Result<(), String> {
+    let config_dir = config_dir().ok_or("Failed to get config directory")?;
+    let key = fs::read(config_dir.join("lockbox").join("secret_key"))
+        .map_err(|_| "Secret key file does not exist".to_string())?;
+    if key.len() != 32 {
+        return Err("The key length is not 32 bytes".to_string());
+    }
+
+    // Generate a random IV (Initialization Vector)
+    let mut iv = [0u8; 16];
+    rand::thread_rng().fill(&mut iv);
+
+    let auth_data = AuthData {
+        username: username.to_string(),
+        password: password.to_string(),
+    };
+    let plaintext = serde_json::to_string(&auth_data).map_err(|e| format!("{:?}", e))?;
+    let cipher = Aes256Cbc::new_from_slices(&key, &iv).map_err(|e| format!("{:?}", e))?;
+    let ciphertext = cipher.encrypt_vec(plaintext.as_bytes());
+
+    let encrypted_data = format!("{}:{}", encode(iv), encode(ciphertext));
+    fs::write("auth/authentication.json", encrypted_data)
+        .map_err(|e| format!("Failed to write account: {:?}", e))
+}
+
+// Erase the account and its vault, leaving the machine ready for a new sign-up
+pub fn remove_account(username: &str) -> Result<(), String> {
+    fs::write(
+        "auth/authentication.json",
+        r#"{"username":"","password":""}"#,
+    )
+    .map_err(|e| format!("Failed to delete account: {:?}", e))?;
+    let dir = vault::user_dir(username)?;
+    if dir.exists() {
+        fs::remove_dir_all(dir).map_err(|e| format!("Failed to delete vault: {:?}", e))?;
+    }
+    Ok(())
+}
+
+// Whether an account has been registered on this machine
+pub fn account_exists() -> bool {
+    fs::read_to_string("auth/authentication.json")
+        .map(|contents| {
+            let contents = contents.trim_matches(char::from(0)).trim();
+            !contents.is_empty() && contents != r#"{"username":"","password":""}"#
+        })
+        .unwrap_or(false)
+}
+
+// Why a login attempt failed, shown inline on the login form
+#[derive(Debug, Clone)]
+pub enum LoginError {
+    EmptyFields,
+    MissingKey,
+    InvalidKey,
+    NoAccount,
+    CorruptedAccount,
+    WrongCredentials,
+    LockedOut(u64),
+    Vault(String),
+}
+
+impl fmt::Display for LoginError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoginError::EmptyFields => write!(f, "Enter your username and password"),
+            LoginError::MissingKey => write!(
+                f,
+                "The secret key file is missing, see the README to create it"
+            ),
+            LoginError::InvalidKey => write!(f, "The secret key must be exactly 32 bytes"),
+            LoginError::NoAccount => write!(f, "No account has been created yet"),
+            LoginError::CorruptedAccount => write!(f, "The account file is corrupted"),
+            LoginError::WrongCredentials => write!(f, "Wrong username or password"),
+            LoginError::LockedOut(seconds) => write!(
+                f,
+                "Too many failed attempts, try again in {} seconds",
+                seconds
+            ),
+            LoginError::Vault(e) => write!(f, "Failed to unlock vault: {}", e),
+        }
+    }
+}
+
+// Check a username and password against the stored account
+pub fn verify_credentials(username: &str, password: &str) -> Result<(), LoginError> {
+    let config_dir = config_dir().ok_or(LoginError::MissingKey)?;
+    let key = fs::read(config_dir.join("lockbox").join("secret_key"))
+        .map_err(|_| LoginError::MissingKey)?;
+    if key.len() != 32 {
+        return Err(LoginError::InvalidKey);
+    }
+
+    let contents =
+        fs::read_to_string("auth/authentication.json").map_err(|_| LoginError::NoAccount)?;
+    let contents = contents.trim_matches(char::from(0));
+    let (iv, ciphertext) = contents.split_once(':').ok_or(LoginError::NoAccount)?;
+
+    let iv = decode(iv).map_err(|_| LoginError::CorruptedAccount)?;
+    let ciphertext = decode(ciphertext).map_err(|_| LoginError::CorruptedAccount)?;
+    let cipher = Aes256Cbc::new_from_slices(&key, &iv).map_err(|_| LoginError::CorruptedAccount)?;
+    // The key decrypts every account, so padding errors mean the file was damaged
+    let decrypted = cipher
+        .decrypt_vec(&ciphertext)
+        .map_err(|_| LoginError::CorruptedAccount)?;
+    let stored_auth: AuthData =
+        serde_json::from_slice(&decrypted).map_err(|_| LoginError::CorruptedAccount)?;
+
+    if stored_auth.username == username && stored_auth.password == password {
+        Ok(())
+    } else {
+        Err(LoginError::WrongCredentials)
+    }
+}
+
+// Vault unlocked on a worker thread, handed back to the UI in a message
+#[derive(Clone)]
+pub struct UnlockedVault(Arc<Mutex<Option<Vault>>>);
+
+impl UnlockedVault {
+    fn take(&self) -> Option<Vault> {
+        self.0.lock().ok()?.take()
+    }
+}
+
+impl fmt::Debug for UnlockedVault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("UnlockedVault")
+    }
+}
+
+// Verify the account and derive the vault key, both slow enough to keep off the UI thread
+fn log_in(username: &str, password: &str) -> Result<UnlockedVault, LoginError> {
+    verify_credentials(username, password)?;
+    let vault = Vault::unlock(username, password).map_err(LoginError::Vault)?;
+    Ok(UnlockedVault(Arc::new(Mutex::new(Some(vault)))))
+}
+
 impl LoginPage {
-    pub fn update(&mut self, message: Message) {
+    pub fn update(&mut self, message: Message) -> Command<Message> {
+        if !message.is_background() {
+            self.last_activity = Some(Instant::now());
+        }
+
         match message {
             // Handle username change
             Message::UsernameChanged(value) => {
@@ -65,109 +435,95 @@ impl LoginPage {
             }
             // Handle login button press
             Message::LoginPressed => {
+                if self.logging_in {
+                    return Command::none();
+                }
+                if let Some(remaining) = self.lockout_remaining() {
+                    self.login_error = Some(LoginError::LockedOut(remaining.as_secs() + 1));
+                    return Command::none();
+                }
                 // Check if username or password is empty
                 if self.username.is_empty() || self.password.is_empty() {
-                    return;
+                    self.login_error = Some(LoginError::EmptyFields);
+                    return Command::none();
                 }
 
-                // Get the platform-specific directory
-                let config_dir = config_dir().expect("Failed to get config directory");
-                let key_file_path = config_dir.join("lockbox").join("secret_key");
-
-                // Check if the key file exists
-                if !key_file_path.exists() {
-                    println!("Secret key file does not exist. Please create it using the following commands:");
-                    println!(r#"mkdir -p "$(dirname "$(dirs::config_dir)/lockbox/secret_key")""#);
-                    println!(
-                        r#"echo "anexampleveryverysecretkey123456" > "$(dirs::config_dir)/lockbox/secret_key""#
+                if !account_exists() {
+                    // No account yet, so ask for the password twice rather than saving a typo
+                    self.signup_form = SignupForm::new(
+                        &self.username,
+                        Some("No account exists yet. Create one to get started.".to_string()),
                     );
-                    return;
-                }
-
-                // Read the key from the file
-                let stored_key =
-                    fs::read(&key_file_path).expect("Failed to read secret key from file");
-                let key = stored_key.as_slice();
-
-                // Convert the key to a string slice
-                // let key_str = std::str::from_utf8(key).expect("Failed to convert key to string");
-                if key.len() != 32 {
-                    panic!("The key length is not 32 bytes. You either never set a key or your key is too short. Follow the programs readme to learn more.");
-                }
-
-                // Generate a random IV (Initialization Vector)
-                let mut rng = rand::thread_rng();
-                let mut iv = [0u8; 16];
-                rng.fill(&mut iv);
-
-                // Encrypt the password
-                let cipher = Aes256Cbc::new_from_slices(key, &iv).unwrap();
-                let ciphertext = cipher.encrypt_vec(self.password.as_bytes());
-
-                // Store the encrypted password and IV
-                let encrypted_data = format!("{}:{}", encode(iv), encode(ciphertext));
-
-                // Create auth data
-                let auth_data = AuthData {
-                    username: self.username.clone(),
-                    password: self.password.clone(),
-                };
-
-                // Serialize auth data to JSON
-                let plaintext = serde_json::to_string(&auth_data).unwrap();
-                let cipher = Aes256Cbc::new_from_slices(key, &iv).unwrap();
-                let ciphertext = cipher.encrypt_vec(plaintext.as_bytes());
-
-                // Open authentication file
-                let mut file = OpenOptions::new()
-                    .read(true)
-                    .write(true)
-                    .create(true)
-                    .open("auth/authentication.json")
-                    .unwrap();
-
-                let mut contents = String::new();
-                file.read_to_string(&mut contents).unwrap();
-
-                // Clean the contents by removing null characters
-                contents = contents.trim_matches(char::from(0)).to_string();
-
-                if contents.trim().is_empty() || contents == r#"{"username":"","password":""}"# {
-                    // First time login, save the credentials
-                    let encrypted_data = format!("{}:{}", encode(iv), encode(ciphertext));
-                    file.set_len(0).unwrap(); // Truncate the file
-                    file.write_all(encrypted_data.as_bytes()).unwrap();
-                    self.authenticated = true;
-                    // Switch to the store page
-                    self.update(Message::SwitchToStorePage);
-                } else {
-                    // Check if the credentials match
-                    let parts: Vec<&str> = contents.split(':').collect();
-                    if parts.len() != 2 {
-                        self.authenticated = false;
-                        return;
-                    }
-
-                    let iv = decode(parts[0]).unwrap();
-                    let ciphertext = decode(parts[1]).unwrap();
-                    let cipher = Aes256Cbc::new_from_slices(key, &iv).unwrap();
-                    let decrypted_data = cipher.decrypt_vec(&ciphertext).unwrap();
-                    let stored_auth: AuthData = serde_json::from_slice(&decrypted_data).unwrap();
+                    self.signing_up = true;
+                    self.password.clear();
+                    return Command::none();
+                }
 
-                    // Verify credentials
-                    if stored_auth.username == self.username
-                        && stored_auth.password == self.password
-                    {
+                self.logging_in = true;
+                self.login_error = None;
+                let username = self.username.clone();
+                let password = self.password.clone();
+                return Command::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || log_in(&username, &password))
+                            .await
+                            .unwrap_or_else(|e| Err(LoginError::Vault(format!("{:?}", e))))
+                    },
+                    Message::LoginFinished,
+                );
+            }
+            Message::LoginFinished(result) => {
+                self.logging_in = false;
+                match result {
+                    Ok(vault) => {
+                        self.failed_attempts = 0;
+                        self.locked_until = None;
                         self.authenticated = true;
+                        let restore_window = match vault.take() {
+                            Some(vault) => self.open_vault(vault),
+                            None => Command::none(),
+                        };
                         // Switch to the store page
-                        self.update(Message::SwitchToStorePage);
-                    } else {
+                        return Command::batch([
+                            restore_window,
+                            self.update(Message::SwitchToStorePage),
+                        ]);
+                    }
+                    Err(e) => {
                         self.authenticated = false;
+                        if let LoginError::WrongCredentials = e {
+                            self.record_failed_attempt();
+                            self.password.clear();
+                        }
+                        self.login_error = Some(e);
                     }
                 }
             }
+            Message::SpinnerTick => {
+                self.spinner_frame = self.spinner_frame.wrapping_add(1);
+            }
+            Message::ModifiersChanged(shift) => {
+                self.shift_held = shift;
+            }
+            // Letters typed in the opposite case to Shift mean Caps Lock is on
+            Message::CharacterTyped(c) => {
+                if c.is_alphabetic() && c.is_uppercase() != c.is_lowercase() {
+                    self.caps_lock = c.is_uppercase() != self.shift_held;
+                }
+            }
             // Handle switching to the store page
+            Message::SwitchToStorePage if matches!(self.state, AppState::EnvPage) => {
+                return self.discard_env(Discard::LeaveEnvPage);
+            }
             Message::SwitchToStorePage => {
+                // Flush pending edits before leaving the vault pages
+                if let Some(vault) = &self.vault {
+                    let saved = self.notes_page.save(vault);
+                    let saved = saved.and(self.passwords_page.save(vault));
+                    if let Err(e) = saved {
+                        self.toasts.error(e);
+                    }
+                }
                 // Logic to switch to the store page
                 self.state = AppState::StorePage; // Update the state
             }
@@ -177,12 +533,884 @@ impl LoginPage {
             }
             Message::EncryptFile => {
                 // Call the function to encrypt a file
-                self.store_page.encrypt_file();
+                let confirmations = self.store_page.encrypt_file();
+                self.ask(confirmations);
+                self.save_recent();
             }
             Message::DecryptFile => {
                 // Call the function to decrypt a file
-                self.store_page.decrypt_file();
+                let confirmations = self.store_page.decrypt_file();
+                self.ask(confirmations);
+                self.save_recent();
+            }
+            Message::CipherSelected(backend) => {
+                self.store_page.backend_selected(backend);
+            }
+            Message::FileHovered => {
+                // Show the drop area while files are dragged over the window
+                self.store_page.file_hovered();
+            }
+            Message::FileDropped(path) => {
+                self.store_page.file_dropped(path);
+            }
+            Message::ProcessDrop => {
+                self.store_page.process_drop();
+            }
+            Message::DismissDrop => {
+                self.store_page.dismiss_drop();
+            }
+            Message::FilesHoveredLeft => {
+                self.store_page.files_hovered_left();
+            }
+            Message::WindowResized(width, height) => {
+                session::window_resized(width, height);
+            }
+            Message::WindowMoved(x, y) => {
+                session::window_moved(x, y);
+            }
+            Message::CloseRequested => {
+                self.exit();
+            }
+            Message::PreviewFile => {
+                // Decrypt into memory only
+                self.store_page.preview_file();
+            }
+            Message::ClosePreview => {
+                self.store_page.close_preview();
+            }
+            Message::OpenForEditing => {
+                self.store_page.open_for_editing();
+            }
+            Message::CheckWorkingCopy => {
+                self.store_page.check_working_copy();
+            }
+            Message::FinishEditing => {
+                // Re-encrypt pending edits and wipe the temp copy
+                if let Err(e) = self.store_page.finish_editing() {
+                    self.toasts.error(e);
+                }
+            }
+            Message::ToggleWatchPanel => {
+                self.store_page.toggle_watch_panel();
+            }
+            Message::ToggleRecentPanel => {
+                self.store_page.toggle_recent_panel();
+            }
+            Message::SelectRecentFile(path) => {
+                self.store_page.select_recent_file(path);
+            }
+            Message::PinFile(path) => {
+                self.store_page.pin_file(path);
+                self.save_recent();
+            }
+            Message::UnpinFile(path) => {
+                self.store_page.unpin_file(&path);
+                self.save_recent();
+            }
+            Message::ClearRecentFiles => {
+                self.store_page.clear_recent();
+                self.save_recent();
+            }
+            Message::ChooseWatchInput => {
+                self.store_page.choose_watch_input();
+                self.save_watch();
+            }
+            Message::ChooseWatchOutput => {
+                self.store_page.choose_watch_output();
+                self.save_watch();
+            }
+            Message::WatchRecipientsChanged(value) => {
+                self.store_page.watch_recipients_changed(value);
+            }
+            Message::ToggleWatchShred(shred) => {
+                self.store_page.watch_shred_toggled(shred);
+                self.save_watch();
+            }
+            Message::ToggleWatching => {
+                self.store_page.toggle_watching();
+                self.save_watch();
+            }
+            Message::WatchedFilesChanged(paths) => {
+                self.store_page.watched_files_changed(paths);
+            }
+            Message::WatchFailed(error) => {
+                self.store_page.watch_failed(error);
+            }
+            Message::ProcessWatchFolder => {
+                // Encrypt dropped files once they stop changing
+                self.store_page.process_watch_folder();
+            }
+            Message::ShredPassesChanged(passes) => {
+                self.store_page.shred_passes_changed(passes);
+            }
+            Message::ShredFile => {
+                // Overwrite and delete the selected file after confirmation
+                let confirmation = self.store_page.shred_file();
+                self.ask(confirmation);
+            }
+            Message::ToggleTextMode => {
+                // Switch between file and text snippet mode
+                self.store_page.toggle_text_mode();
+            }
+            Message::SnippetEdited(index, value) => {
+                self.store_page.snippet_edited(index, value);
+            }
+            Message::SnippetNewLine(index) => {
+                self.store_page.snippet_new_line(index);
+            }
+            Message::PasteSnippet => {
+                return clipboard::read(Message::SnippetPasted);
+            }
+            Message::SnippetPasted(text) => {
+                if let Some(text) = text {
+                    self.store_page.snippet_pasted(&text);
+                }
+            }
+            Message::EncryptText => {
+                // Encrypt the snippet into armored age text
+                self.store_page.encrypt_text();
+            }
+            Message::DecryptText => {
+                // Decrypt the pasted armored ciphertext
+                self.store_page.decrypt_text();
+            }
+            Message::ToggleRevealText => {
+                self.store_page.toggle_reveal_text();
+            }
+            Message::CopyTextOutput => {
+                // Plaintext is a secret and gets wiped; armored ciphertext does not
+                match self.store_page.text_output() {
+                    Some((output, true)) => {
+                        self.clipboard_generation += 1;
+                        self.clipboard_secret = Some(output.clone());
+                        return crate::clipboard::copy_secret(output, self.clipboard_generation);
+                    }
+                    Some((output, false)) => return clipboard::write(output),
+                    None => {}
+                }
+            }
+            Message::SwitchToNotesPage => {
+                // Notes need an unlocked vault
+                if let Some(vault) = &self.vault {
+                    self.notes_page.load(vault);
+                    self.state = AppState::NotesPage;
+                }
+            }
+            Message::NewNote => {
+                self.notes_page.new_note();
+            }
+            Message::SelectNote(id) => {
+                self.notes_page.select_note(id);
+            }
+            Message::DeleteNote => {
+                self.notes_page.delete_note();
+            }
+            Message::NoteSearchChanged(value) => {
+                self.notes_page.search_changed(value);
+            }
+            Message::NoteTitleChanged(value) => {
+                self.notes_page.title_changed(value);
+            }
+            Message::NoteTagsChanged(value) => {
+                self.notes_page.tags_changed(value);
+            }
+            Message::NoteBodyEdited(index, value) => {
+                self.notes_page.body_edited(index, value);
+            }
+            Message::NoteBodyNewLine(index) => {
+                self.notes_page.body_new_line(index);
+            }
+            Message::PasteNoteBody => {
+                return clipboard::read(Message::NoteBodyPasted);
+            }
+            Message::NoteBodyPasted(text) => {
+                if let Some(text) = text {
+                    self.notes_page.body_pasted(&text);
+                }
+            }
+            Message::AutosaveNotes => {
+                // Encrypt and write pending note edits
+                // A failure shows in the page status and is retried on the next tick
+                if let Some(vault) = &self.vault {
+                    let _ = self.notes_page.save(vault);
+                }
+            }
+            Message::SwitchToPasswordsPage => {
+                // Passwords need an unlocked vault
+                if let Some(vault) = &self.vault {
+                    self.passwords_page.load(vault);
+                    self.state = AppState::PasswordsPage;
+                }
+            }
+            Message::NewEntry => {
+                self.passwords_page.new_entry();
+            }
+            Message::SelectEntry(id) => {
+                self.passwords_page.select_entry(id);
+            }
+            Message::DeleteEntry => {
+                self.passwords_page.delete_entry();
+            }
+            Message::EntrySearchChanged(value) => {
+                self.passwords_page.search_changed(value);
+            }
+            Message::EntryFieldChanged(field, value) => {
+                self.passwords_page.field_changed(field, value);
+            }
+            Message::AddCustomField => {
+                self.passwords_page.add_custom_field();
+            }
+            Message::RemoveCustomField(index) => {
+                self.passwords_page.remove_custom_field(index);
+            }
+            Message::CustomFieldNameChanged(index, value) => {
+                self.passwords_page.custom_field_name_changed(index, value);
+            }
+            Message::CustomFieldValueChanged(index, value) => {
+                self.passwords_page.custom_field_value_changed(index, value);
+            }
+            Message::ToggleRevealPassword => {
+                self.passwords_page.toggle_reveal_password();
+            }
+            Message::CopyEntryField(field) => {
+                // Copy the field and schedule wiping it from the clipboard
+                if let Some(value) = self.passwords_page.selected_field(field) {
+                    self.clipboard_generation += 1;
+                    self.clipboard_secret = Some(value.clone());
+                    return crate::clipboard::copy_secret(value, self.clipboard_generation);
+                }
+            }
+            Message::ClearClipboard(generation) => {
+                // Ignore timers from copies that were since replaced
+                if generation == self.clipboard_generation {
+                    if let Some(secret) = self.clipboard_secret.take() {
+                        return crate::clipboard::clear_secret(secret);
+                    }
+                }
+            }
+            Message::ClipboardChecked(unchanged) => {
+                if unchanged {
+                    return clipboard::write(String::new());
+                }
+            }
+            Message::ToggleGeneratorMode => {
+                self.passwords_page.toggle_generator_mode();
+            }
+            Message::GeneratorLengthChanged(value) => {
+                self.passwords_page.generator_length_changed(value);
+            }
+            Message::GeneratorClassToggled(class, enabled) => {
+                self.passwords_page.generator_class_toggled(class, enabled);
+            }
+            Message::GeneratePassword => {
+                self.passwords_page.generate();
+            }
+            Message::UseGeneratedPassword => {
+                self.passwords_page.use_generated();
+            }
+            Message::AutosavePasswords => {
+                // Encrypt and write pending password edits
+                // A failure shows in the page status and is retried on the next tick
+                if let Some(vault) = &self.vault {
+                    let _ = self.passwords_page.save(vault);
+                }
+            }
+            Message::ToggleImportPanel => {
+                self.passwords_page.toggle_import();
+            }
+            Message::ChooseImportSource(format) => {
+                self.passwords_page.choose_import_source(format);
+            }
+            Message::ChooseImportKeyfile => {
+                self.passwords_page.choose_import_keyfile();
+            }
+            Message::ImportPasswordChanged(value) => {
+                self.passwords_page.import_password_changed(value);
+            }
+            Message::PreviewImport => {
+                // Dry run that only lists what would be imported
+                self.passwords_page.preview_import();
+            }
+            Message::ToggleOverwriteConflicts(overwrite) => {
+                self.passwords_page.overwrite_conflicts_toggled(overwrite);
+            }
+            Message::ConfirmImport => {
+                self.passwords_page.confirm_import();
+            }
+            Message::SwitchToEnvPage => {
+                self.state = AppState::EnvPage;
+            }
+            Message::NewEnvFile => {
+                return self.discard_env(Discard::NewEnvFile);
+            }
+            Message::OpenEnvFile => {
+                return self.discard_env(Discard::OpenEnvFile);
+            }
+            Message::SaveEnvFile => {
+                self.env_page.save_file();
+            }
+            Message::AddEnvVar => {
+                self.env_page.add_var();
+            }
+            Message::RemoveEnvVar(index) => {
+                self.env_page.remove_var(index);
+            }
+            Message::EnvKeyChanged(index, value) => {
+                self.env_page.key_changed(index, value);
+            }
+            Message::EnvValueChanged(index, value) => {
+                self.env_page.value_changed(index, value);
             }
+            Message::ToggleRevealEnv => {
+                self.env_page.toggle_reveal();
+            }
+            Message::SwitchToHistoryPage => {
+                self.state = AppState::HistoryPage;
+            }
+            Message::ExportHistory(format) => {
+                self.history_page.export(format);
+            }
+            Message::ClearHistory => {
+                self.history_page.clear();
+            }
+            Message::ExpireToasts => {
+                self.toasts.expire();
+            }
+            Message::DismissToast(id) => {
+                self.toasts.dismiss(id);
+            }
+            Message::ConfirmAction => {
+                if let Some(confirmation) = self.confirmations.pop_front() {
+                    if confirmation.dont_ask() {
+                        if let Err(e) = stop_asking(confirmation.action()) {
+                            self.toasts.error(e);
+                        }
+                    }
+                    match confirmation.confirm() {
+                        Pending::DeleteAccount => return self.delete_account(),
+                        Pending::Discard(next) => return self.env_discarded(next),
+                        pending => {
+                            let confirmations = self.store_page.confirmed(pending);
+                            self.ask(confirmations);
+                            self.save_recent();
+                        }
+                    }
+                }
+            }
+            Message::CancelAction => {
+                // "Don't ask again" only remembers a yes
+                self.confirmations.pop_front();
+            }
+            Message::ToggleDontAsk(dont_ask) => {
+                if let Some(confirmation) = self.confirmations.front_mut() {
+                    confirmation.dont_ask_toggled(dont_ask);
+                }
+            }
+            Message::ToggleConfirmation(action, ask) => {
+                self.settings_page.confirmation_toggled(action, ask);
+            }
+            Message::DeleteAccount => {
+                if config::current().confirm.delete_account {
+                    self.ask(Some(Confirmation::new(
+                        confirm::Action::DeleteAccount,
+                        format!(
+                            "Delete the account {} and everything in its vault? Notes, \
+                            passwords and recovery setup are erased for good. Files \
+                            encrypted on the store page are not affected.",
+                            self.username
+                        ),
+                        Pending::DeleteAccount,
+                    )));
+                } else {
+                    return self.delete_account();
+                }
+            }
+            Message::SwitchToSettingsPage => {
+                self.settings_page.open();
+                self.state = AppState::SettingsPage;
+            }
+            Message::ThemeSelected(setting) => {
+                self.settings_page.theme_selected(setting);
+            }
+            Message::OutputDirectoryChanged(value) => {
+                self.settings_page.output_directory_changed(value);
+            }
+            Message::ChooseOutputDirectory => {
+                self.settings_page.choose_output_directory();
+            }
+            Message::ToggleKeepOriginals(keep) => {
+                self.settings_page.keep_originals_toggled(keep);
+            }
+            Message::ToggleArmorOutput(armor) => {
+                self.settings_page.armor_toggled(armor);
+            }
+            Message::ScryptWorkFactorChanged(value) => {
+                self.settings_page.scrypt_log_n_changed(value);
+            }
+            Message::AutoLockChanged(value) => {
+                self.settings_page.auto_lock_changed(value);
+            }
+            Message::MinPasswordScoreChanged(score) => {
+                self.settings_page.min_password_score_changed(score);
+            }
+            Message::WindowWidthChanged(value) => {
+                self.settings_page.window_width_changed(value);
+            }
+            Message::WindowHeightChanged(value) => {
+                self.settings_page.window_height_changed(value);
+            }
+            // Resize the window straight away when its saved size changes
+            Message::SaveSettings => {
+                if let Some(window) = self.settings_page.save() {
+                    return iced::window::resize(window.width, window.height);
+                }
+            }
+            Message::ResetSettings => {
+                if let Some(window) = self.settings_page.reset() {
+                    return iced::window::resize(window.width, window.height);
+                }
+            }
+            Message::CloseSettings => {
+                self.settings_page.discard();
+                self.state = AppState::StorePage;
+            }
+            Message::AutoLockTick => {
+                if self.idle_timeout_reached() {
+                    return self.lock();
+                }
+            }
+            Message::KeyboardShortcut(shortcut) => {
+                return self.handle_shortcut(shortcut);
+            }
+            Message::SwitchToBackupPage => {
+                self.backup_page.reset();
+                self.state = AppState::BackupPage;
+            }
+            Message::BackupPassphraseChanged(value) => {
+                self.backup_page.passphrase_changed(value);
+            }
+            Message::BackupConfirmChanged(value) => {
+                self.backup_page.confirm_changed(value);
+            }
+            Message::ExportBackup => {
+                // Bundle the logged in user's profile into an encrypted archive
+                self.backup_page.export(&self.username);
+            }
+            Message::ToggleRestoreForm => {
+                self.restoring = !self.restoring;
+                self.restore_form = RestoreForm::default();
+            }
+            Message::ChooseRestoreSource => {
+                self.restore_form.choose_source();
+            }
+            Message::RestorePassphraseChanged(value) => {
+                self.restore_form.passphrase_changed(value);
+            }
+            Message::ToggleRestoreReplace(replace) => {
+                self.restore_form.replace_toggled(replace);
+            }
+            Message::RestoreBackup => {
+                // Prefill the login form with the restored account
+                if let Some(username) = self.restore_form.restore() {
+                    self.username = username;
+                    self.password.clear();
+                }
+            }
+            Message::SwitchToRecoveryPage => {
+                self.recovery_page.reset();
+                self.state = AppState::RecoveryPage;
+            }
+            Message::RecoveryThresholdChanged(value) => {
+                self.recovery_page.threshold_changed(value);
+            }
+            Message::RecoverySharesChanged(value) => {
+                self.recovery_page.shares_changed(value);
+            }
+            Message::CreateRecoveryShares => {
+                if let Some(vault) = &self.vault {
+                    self.recovery_page.create_shares(vault);
+                }
+            }
+            Message::CopyRecoveryShare(index) => {
+                if let Some(share) = self.recovery_page.share(index) {
+                    self.clipboard_generation += 1;
+                    self.clipboard_secret = Some(share.clone());
+                    return crate::clipboard::copy_secret(share, self.clipboard_generation);
+                }
+            }
+            Message::SaveRecoveryShares => {
+                self.recovery_page.save_shares(&self.username);
+            }
+            Message::CreateRecoveryKit => {
+                if let Some(vault) = &self.vault {
+                    self.recovery_page.create_kit(&self.username, vault);
+                }
+            }
+            Message::ToggleRecoverForm => {
+                self.recovering = !self.recovering;
+                self.recover_form = RecoverForm::new();
+            }
+            Message::RecoverUsernameChanged(value) => {
+                self.recover_form.username_changed(value);
+            }
+            Message::RecoverShareChanged(index, value) => {
+                self.recover_form.share_changed(index, value);
+            }
+            Message::AddRecoverShare => {
+                self.recover_form.add_share();
+            }
+            Message::ToggleRecoverMethod => {
+                self.recover_form.method_toggled();
+            }
+            Message::RecoverWordsChanged(value) => {
+                self.recover_form.words_changed(value);
+            }
+            Message::RecoverPasswordChanged(value) => {
+                self.recover_form.password_changed(value);
+            }
+            Message::RecoverConfirmChanged(value) => {
+                self.recover_form.confirm_changed(value);
+            }
+            Message::ToggleSignupForm => {
+                self.signing_up = !self.signing_up;
+                self.signup_form = SignupForm::new(&self.username, None);
+            }
+            Message::SignupUsernameChanged(value) => {
+                self.signup_form.username_changed(value);
+            }
+            Message::SignupPasswordChanged(value) => {
+                self.signup_form.password_changed(value);
+            }
+            Message::SignupConfirmChanged(value) => {
+                self.signup_form.confirm_changed(value);
+            }
+            Message::ToggleRevealSignup => {
+                self.signup_form.toggle_reveal();
+            }
+            Message::SignupPressed => {
+                // Log straight in with the new account
+                if let Some((username, password)) = self.signup_form.submit() {
+                    self.signing_up = false;
+                    self.username = username;
+                    self.password = password;
+                    return self.update(Message::LoginPressed);
+                }
+            }
+            Message::RecoverWithShares => {
+                // Prefill the login form with the recovered account
+                if let Some(username) = self.recover_form.recover() {
+                    self.username = username;
+                    self.password.clear();
+                }
+            }
+        }
+
+        Command::none()
+    }
+
+    // Take over the vault unlocked for the user that just authenticated, restoring their window
+    fn open_vault(&mut self, vault: Vault) -> Command<Message> {
+        self.store_page.load_watch(&vault);
+        self.store_page.load_recent(&vault);
+        let window = session::restore(&vault);
+        self.vault = Some(vault);
+        window.map_or_else(Command::none, |window| window.restore())
+    }
+
+    // Whether the vault has been left alone longer than the auto-lock timeout
+    fn idle_timeout_reached(&self) -> bool {
+        let minutes = config::current().security.auto_lock_minutes;
+        match self.last_activity {
+            Some(last) if self.authenticated && minutes > 0 => {
+                last.elapsed() >= Duration::from_secs(u64::from(minutes) * 60)
+            }
+            _ => false,
+        }
+    }
+
+    // Time left before another login attempt is allowed
+    fn lockout_remaining(&self) -> Option<Duration> {
+        self.locked_until
+            .and_then(|until| until.checked_duration_since(Instant::now()))
+    }
+
+    // Lock the form after repeated failures, doubling the wait each time
+    fn record_failed_attempt(&mut self) {
+        self.failed_attempts += 1;
+        if self.failed_attempts >= MAX_ATTEMPTS {
+            let doublings = (self.failed_attempts - MAX_ATTEMPTS).min(5);
+            self.locked_until = Some(Instant::now() + LOCKOUT * 2u32.pow(doublings));
+        }
+    }
+
+    // Route a keyboard shortcut to the page it applies to
+    fn handle_shortcut(&mut self, shortcut: Shortcut) -> Command<Message> {
+        // Only Esc gets past an open confirmation, cancelling it
+        if !self.confirmations.is_empty() {
+            if let Shortcut::CloseHelp = shortcut {
+                return self.update(Message::CancelAction);
+            }
+            return Command::none();
+        }
+        match (&self.state, shortcut) {
+            (AppState::LoginPage, Shortcut::FocusNext | Shortcut::FocusPrevious)
+                if !self.restoring && !self.recovering && !self.signing_up =>
+            {
+                self.cycle_focus(matches!(shortcut, Shortcut::FocusNext));
+            }
+            (AppState::StorePage, Shortcut::SelectFile) => {
+                return self.update(Message::TriggerFileSelection)
+            }
+            // Encrypt and decrypt act on whatever the page shows, and only there
+            (AppState::StorePage, Shortcut::Encrypt) if self.store_page.is_text_mode() => {
+                return self.update(Message::EncryptText)
+            }
+            (AppState::StorePage, Shortcut::Decrypt) if self.store_page.is_text_mode() => {
+                return self.update(Message::DecryptText)
+            }
+            (AppState::StorePage, Shortcut::Encrypt) if self.store_page.shows_files() => {
+                return self.update(Message::EncryptFile)
+            }
+            (AppState::StorePage, Shortcut::Decrypt) if self.store_page.shows_files() => {
+                return self.update(Message::DecryptFile)
+            }
+            (AppState::StorePage, Shortcut::ToggleHelp) => self.store_page.toggle_help(),
+            (AppState::StorePage, Shortcut::CloseHelp) => self.store_page.close_help(),
+            (AppState::StorePage, Shortcut::PreviousLine | Shortcut::NextLine) => self
+                .store_page
+                .move_snippet_line(matches!(shortcut, Shortcut::NextLine)),
+            (AppState::NotesPage, Shortcut::PreviousLine | Shortcut::NextLine) => self
+                .notes_page
+                .move_body_line(matches!(shortcut, Shortcut::NextLine)),
+            (_, Shortcut::Lock) if self.authenticated => return self.lock(),
+            _ => {}
+        }
+        Command::none()
+    }
+
+    // Move focus between the username and password fields
+    fn cycle_focus(&mut self, forward: bool) {
+        let to_password = if self.username_input.is_focused() {
+            true
+        } else if self.password_input.is_focused() {
+            false
+        } else {
+            !forward
+        };
+
+        // With two fields both directions just swap focus
+        if to_password {
+            self.username_input.unfocus();
+            self.password_input.focus();
+        } else {
+            self.password_input.unfocus();
+            self.username_input.focus();
+        }
+    }
+
+    // Save everything, drop the vault keys, decrypted pages and any copied secret,
+    // and return to login
+    fn lock(&mut self) -> Command<Message> {
+        // Earlier toasts may name files, but problems saving should still show after locking
+        self.toasts.clear();
+        let saved = self.save_all();
+        if self.keeps_unsaved("lock", saved)
+            || self.keeps_working_copy("lock")
+            || self.keeps_env_changes(Discard::Lock)
+        {
+            // Auto-lock tries again after another idle period
+            self.last_activity = Some(Instant::now());
+            return Command::none();
+        }
+        session::forget_user();
+
+        self.store_page = StorePage::default();
+        self.notes_page = NotesPage::default();
+        self.passwords_page = PasswordsPage::default();
+        self.env_page = EnvPage::default();
+        self.backup_page = BackupPage::default();
+        self.history_page = HistoryPage::default();
+        self.confirmations.clear();
+        self.recovery_page.reset();
+        self.vault = None;
+        self.authenticated = false;
+        self.password.clear();
+        self.state = AppState::LoginPage;
+        self.password_input.focus();
+
+        // Pending clear timers become no-ops, the copied secret is cleared right away
+        self.clipboard_generation += 1;
+        match self.clipboard_secret.take() {
+            Some(secret) => crate::clipboard::clear_secret(secret),
+            None => Command::none(),
+        }
+    }
+
+    // Flush vault pages, working copies and the session before locking or quitting
+    fn save_all(&mut self) -> Result<(), String> {
+        let mut saved = Ok(());
+        if let Some(vault) = &self.vault {
+            saved = self.notes_page.save(vault);
+            saved = saved.and(self.passwords_page.save(vault));
+            self.store_page.save_watch(vault);
+            self.store_page.save_recent(vault);
+        }
+        if let Err(e) = self.store_page.finish_editing() {
+            self.toasts.error(e);
+        }
+        if let Err(e) = session::save(self.vault.as_ref()) {
+            self.toasts.error(e);
+        }
+        saved
+    }
+
+    // Close the window once everything is saved
+    fn exit(&mut self) {
+        let saved = self.save_all();
+        self.exiting = !self.keeps_unsaved("quit", saved)
+            && !self.keeps_working_copy("quit")
+            && !self.keeps_env_changes(Discard::Quit);
+    }
+
+    // Whether the env page has unsaved changes the user is now asked to discard
+    fn keeps_env_changes(&mut self, next: Discard) -> bool {
+        if !self.env_page.is_dirty() || !config::current().confirm.discard_changes {
+            return false;
+        }
+        // Auto-lock retries while the question is still open
+        let asked = self
+            .confirmations
+            .iter()
+            .any(|waiting| waiting.action() == confirm::Action::DiscardChanges);
+        if !asked {
+            self.ask(Some(Confirmation::new(
+                confirm::Action::DiscardChanges,
+                next.message(),
+                Pending::Discard(next),
+            )));
         }
+        true
     }
+
+    fn discard_env(&mut self, next: Discard) -> Command<Message> {
+        if self.keeps_env_changes(next) {
+            return Command::none();
+        }
+        self.env_discarded(next)
+    }
+
+    // Carry on with what the unsaved env file was holding up
+    fn env_discarded(&mut self, next: Discard) -> Command<Message> {
+        match next {
+            Discard::NewEnvFile => self.env_page.new_file(),
+            Discard::OpenEnvFile => self.env_page.open_file(),
+            Discard::LeaveEnvPage => {
+                if self.env_page.is_dirty() {
+                    self.env_page = EnvPage::default();
+                }
+                self.state = AppState::StorePage;
+            }
+            Discard::Lock => {
+                self.env_page = EnvPage::default();
+                return self.lock();
+            }
+            Discard::Quit => {
+                self.env_page = EnvPage::default();
+                self.exit();
+            }
+        }
+        Command::none()
+    }
+
+    // Whether notes or passwords failed to save. Their edits only live in the
+    // pages, so locking or quitting waits until a save goes through.
+    fn keeps_unsaved(&mut self, action: &str, saved: Result<(), String>) -> bool {
+        match saved {
+            Ok(()) => false,
+            Err(e) => {
+                self.toasts.error(format!(
+                    "Could not {}: unsaved changes failed to save: {}",
+                    action, e
+                ));
+                true
+            }
+        }
+    }
+
+    // Whether a working copy with edits that failed to save is still open. It
+    // would be wiped with the store page, so locking or quitting waits for it.
+    fn keeps_working_copy(&mut self, action: &str) -> bool {
+        if !self.store_page.is_editing() {
+            return false;
+        }
+        self.toasts.error(format!(
+            "Could not {}: the working copy has changes that failed to save",
+            action
+        ));
+        true
+    }
+
+    // Hand operations the store page finished to the history page, toasting each one
+    pub fn collect_operations(&mut self) {
+        for operation in self.store_page.take_operations() {
+            self.toasts.push(&operation);
+            self.history_page.push(operation);
+        }
+    }
+
+    // Queue questions for the modal, folding files from a later drop into a waiting one
+    fn ask(&mut self, confirmations: impl IntoIterator<Item = Confirmation>) {
+        for confirmation in confirmations {
+            if !self
+                .confirmations
+                .iter_mut()
+                .any(|waiting| waiting.merge(&confirmation))
+            {
+                self.confirmations.push_back(confirmation);
+            }
+        }
+    }
+
+    // Erase the logged in account, dropping its vault unsaved before locking
+    fn delete_account(&mut self) -> Command<Message> {
+        if let Err(e) = self.store_page.finish_editing() {
+            self.toasts.error(e);
+        }
+        if self.keeps_working_copy("delete the account") {
+            return Command::none();
+        }
+        match remove_account(&self.username) {
+            Ok(()) => {
+                self.vault = None;
+                let command = self.lock();
+                self.username.clear();
+                command
+            }
+            Err(e) => {
+                self.settings_page.delete_failed(e);
+                Command::none()
+            }
+        }
+    }
+
+    // Persist recent files and favorites to the vault
+    fn save_recent(&mut self) {
+        if let Some(vault) = &self.vault {
+            self.store_page.save_recent(vault);
+        }
+    }
+
+    // Persist the watch folder settings to the vault
+    fn save_watch(&mut self) {
+        if let Some(vault) = &self.vault {
+            self.store_page.save_watch(vault);
+        }
+    }
+}
+
+// Remember a "don't ask again" in the config file
+fn stop_asking(action: confirm::Action) -> Result<(), String> {
+    let mut config = config::current();
+    config.confirm.set(action, false);
+    config.save()
 }
@@ -2,27 +2,60 @@
 
 use iced::widget::Image;
 use iced::{
-    text_input, Alignment, Background, Button, Color, Column, Container, Element, Length, Sandbox,
-    Settings, Text, TextInput,
+    executor, text_input, time, Alignment, Application, Background, Button, Color, Column, Command,
+    Container, Element, Length, Row, Settings, Subscription, Text, TextInput,
 };
+use std::time::Duration;
 
+mod backup;
+mod cipher;
+mod clipboard;
+mod config;
+mod confirm;
+#[cfg(unix)]
+mod daemon;
+mod dragdrop;
+mod editor;
+mod envfile;
+mod generator;
+mod gitfilter;
+mod history;
+mod import;
+mod kit;
 mod login;
+mod notes;
+mod passwords;
+mod preview;
+mod recent;
+mod recovery;
+mod session;
+mod settings;
+mod shortcuts;
+mod shred;
+mod signup;
+mod spinner;
 mod store;
+mod theme;
+mod toast;
+mod vault;
+mod watch;
+mod workcopy;
 use login::{LoginPage, Message};
-use store::StorePage;
+use spinner::Spinner;
 
-// Define all of the app states
-#[derive(Debug, Clone)]
+// Define all of the app states, starting on the login page
+#[derive(Debug, Clone, Default)]
 pub enum AppState {
+    #[default]
     LoginPage,
     StorePage,
-}
-
-// Default state is the login page
-impl Default for AppState {
-    fn default() -> Self {
-        AppState::LoginPage
-    }
+    NotesPage,
+    PasswordsPage,
+    BackupPage,
+    RecoveryPage,
+    EnvPage,
+    SettingsPage,
+    HistoryPage,
 }
 
 // Custom style for the text input fields
@@ -31,11 +64,12 @@ struct CustomTextInput;
 impl text_input::StyleSheet for CustomTextInput {
     // Style for active state
     fn active(&self) -> text_input::Style {
+        let palette = theme::palette();
         text_input::Style {
-            background: Background::Color(Color::WHITE),
+            background: Background::Color(palette.input_background),
             border_radius: 5.0,
             border_width: 1.0,
-            border_color: Color::from_rgb(66.0 / 255.0, 144.0 / 255.0, 245.0 / 255.0), // Set to #4290f5
+            border_color: palette.border,
         }
     }
 
@@ -46,27 +80,31 @@ impl text_input::StyleSheet for CustomTextInput {
 
     // Color for placeholder text
     fn placeholder_color(&self) -> Color {
-        Color::from_rgb(0.5, 0.5, 0.5)
+        theme::palette().placeholder
     }
 
     // Color for input value text
     fn value_color(&self) -> Color {
-        Color::BLACK
+        theme::palette().input_text
     }
 
     // Color for text selection
     fn selection_color(&self) -> Color {
-        Color::from_rgb(66.0 / 255.0, 144.0 / 255.0, 245.0 / 255.0) // Set to #4290f5
+        theme::palette().primary
     }
 }
 
-// Implement the Sandbox trait for LoginPage
-impl Sandbox for LoginPage {
+// Implement the Application trait for LoginPage
+impl Application for LoginPage {
+    type Executor = executor::Default;
     type Message = Message;
+    type Flags = ();
 
-    // Create a new instance of LoginPage
-    fn new() -> Self {
-        Self::default()
+    // Create a new instance of LoginPage with the saved theme applied
+    fn new(_flags: ()) -> (Self, Command<Message>) {
+        let mut page = Self::default();
+        page.settings_page.load();
+        (page, Command::none())
     }
 
     // Set the title of the application
@@ -74,14 +112,142 @@ impl Sandbox for LoginPage {
         String::from("lockbox")
     }
 
-    // Update the state based on the received message
-    fn update(&mut self, message: Message) {
-        self.update(message);
+    // Fill the window behind the page with the theme background
+    fn background_color(&self) -> Color {
+        theme::palette().background
+    }
+
+    // Update the state based on the received message, then toast any operations it finished
+    fn update(&mut self, message: Message) -> Command<Message> {
+        let command = self.update(message);
+        self.collect_operations();
+        command
+    }
+
+    // Quit only after a close request has saved the session
+    fn should_exit(&self) -> bool {
+        self.exiting
     }
 
-    // Define the view for the application
-    fn view(&mut self) -> Element<Message> {
+    // Autosave pending edits, watch working copies and the watch folder, take keys and dropped files, auto-lock, and track the window
+    fn subscription(&self) -> Subscription<Message> {
+        let mut subscriptions = vec![session::events()];
+
+        if self.store_page.is_editing() {
+            subscriptions
+                .push(time::every(Duration::from_secs(1)).map(|_| Message::CheckWorkingCopy));
+        }
+        if let Some(folder) = self.store_page.watched_folder() {
+            subscriptions.push(watch::watch(folder));
+        }
+        if self.store_page.is_drop_waiting() {
+            subscriptions
+                .push(time::every(Duration::from_millis(100)).map(|_| Message::ProcessDrop));
+        }
+        if self.store_page.is_watch_waiting() {
+            subscriptions
+                .push(time::every(Duration::from_secs(1)).map(|_| Message::ProcessWatchFolder));
+        }
+
+        // Lock has to work from every page once logged in
+        if self.authenticated || matches!(self.state, AppState::LoginPage) {
+            subscriptions.push(shortcuts::events());
+        }
+        if let AppState::LoginPage = self.state {
+            subscriptions.push(shortcuts::typing_events());
+        }
+        if self.authenticated && config::current().security.auto_lock_minutes > 0 {
+            subscriptions.push(time::every(Duration::from_secs(5)).map(|_| Message::AutoLockTick));
+        }
+        if !self.toasts.is_empty() {
+            subscriptions
+                .push(time::every(Duration::from_millis(500)).map(|_| Message::ExpireToasts));
+        }
+        if self.logging_in {
+            subscriptions
+                .push(time::every(Duration::from_millis(50)).map(|_| Message::SpinnerTick));
+        }
+
         match self.state {
+            AppState::StorePage => subscriptions.push(dragdrop::events()),
+            AppState::NotesPage if self.notes_page.is_dirty() => subscriptions
+                .push(time::every(Duration::from_secs(2)).map(|_| Message::AutosaveNotes)),
+            AppState::PasswordsPage if self.passwords_page.is_dirty() => subscriptions
+                .push(time::every(Duration::from_secs(2)).map(|_| Message::AutosavePasswords)),
+            _ => {}
+        }
+
+        Subscription::batch(subscriptions)
+    }
+
+    // Define the view for the application, drawn with the theme's text color
+    fn view(&mut self) -> Element<'_, Message> {
+        let page: Element<Message> = match self.state {
+            // Confirmations replace the page so nothing else can be clicked until answered
+            _ if !self.confirmations.is_empty() => {
+                let logo = Container::new(
+                    Image::new("images/logo.png")
+                        .width(Length::Units(40))
+                        .height(Length::Units(40)),
+                )
+                .padding(10);
+
+                let container = Container::new(self.confirmations[0].view())
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .center_x()
+                    .center_y();
+
+                Column::new().push(logo).push(container).into()
+            }
+            AppState::LoginPage if self.recovering => {
+                let logo = Container::new(
+                    Image::new("images/logo.png")
+                        .width(Length::Units(40))
+                        .height(Length::Units(40)),
+                )
+                .padding(10);
+
+                let container = Container::new(self.recover_form.view())
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .center_x()
+                    .center_y();
+
+                Column::new().push(logo).push(container).into()
+            }
+            AppState::LoginPage if self.signing_up => {
+                let logo = Container::new(
+                    Image::new("images/logo.png")
+                        .width(Length::Units(40))
+                        .height(Length::Units(40)),
+                )
+                .padding(10);
+
+                let container = Container::new(self.signup_form.view())
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .center_x()
+                    .center_y();
+
+                Column::new().push(logo).push(container).into()
+            }
+            AppState::LoginPage if self.restoring => {
+                let logo = Container::new(
+                    Image::new("images/logo.png")
+                        .width(Length::Units(40))
+                        .height(Length::Units(40)),
+                )
+                .padding(10);
+
+                let container = Container::new(self.restore_form.view())
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .center_x()
+                    .center_y();
+
+                Column::new().push(logo).push(container).into()
+            }
             AppState::LoginPage => {
                 let logo = Container::new(
                     Image::new("images/logo.png")
@@ -105,7 +271,8 @@ impl Sandbox for LoginPage {
                 .padding(10)
                 .size(20)
                 .width(Length::Units(200)) // Set fixed width
-                .style(CustomTextInput);
+                .style(CustomTextInput)
+                .on_submit(Message::LoginPressed);
 
                 let password_input = TextInput::new(
                     &mut self.password_input,
@@ -117,19 +284,63 @@ impl Sandbox for LoginPage {
                 .size(20)
                 .width(Length::Units(200)) // Set fixed width
                 .style(CustomTextInput)
+                .on_submit(Message::LoginPressed)
                 .password();
 
-                let login_button = Button::new(&mut self.login_button, Text::new("login"))
-                    .on_press(Message::LoginPressed);
+                // Disable the button while a login is already running
+                let mut login_button = Button::new(&mut self.login_button, Text::new("login"));
+                if !self.logging_in {
+                    login_button = login_button.on_press(Message::LoginPressed);
+                }
+
+                let signup_button = Button::new(&mut self.signup_button, Text::new("sign up"))
+                    .on_press(Message::ToggleSignupForm);
+
+                let restore_button =
+                    Button::new(&mut self.restore_button, Text::new("restore backup"))
+                        .on_press(Message::ToggleRestoreForm);
+
+                let recover_button =
+                    Button::new(&mut self.recover_button, Text::new("recover account"))
+                        .on_press(Message::ToggleRecoverForm);
 
-                let content = Column::new()
+                let mut content = Column::new()
                     .padding(20)
                     .spacing(20)
                     .align_items(Alignment::Center)
                     .push(logo_full) // Add full logo to the column
                     .push(username_input)
-                    .push(password_input)
-                    .push(login_button);
+                    .push(password_input);
+
+                if self.caps_lock {
+                    content = content.push(
+                        Text::new("Caps Lock is on")
+                            .size(14)
+                            .color(Color::from_rgb(0.86, 0.59, 0.26)),
+                    );
+                }
+                if self.logging_in {
+                    content = content.push(
+                        Row::new()
+                            .spacing(10)
+                            .align_items(Alignment::Center)
+                            .push(Spinner::new(self.spinner_frame).view(20))
+                            .push(Text::new("Logging in...").size(16)),
+                    );
+                }
+                if let Some(error) = &self.login_error {
+                    content = content.push(
+                        Text::new(error.to_string())
+                            .size(16)
+                            .color(Color::from_rgb(0.8, 0.2, 0.2)),
+                    );
+                }
+
+                let content = content
+                    .push(login_button)
+                    .push(signup_button)
+                    .push(restore_button)
+                    .push(recover_button);
 
                 let container = Container::new(content)
                     .width(Length::Fill)
@@ -146,17 +357,92 @@ impl Sandbox for LoginPage {
                 // Store page view
                 self.store_page.view()
             }
-        }
+            AppState::NotesPage => {
+                // Secure notes view
+                self.notes_page.view()
+            }
+            AppState::PasswordsPage => {
+                // Password vault view
+                self.passwords_page.view()
+            }
+            AppState::BackupPage => {
+                // Backup export view
+                self.backup_page.view()
+            }
+            AppState::RecoveryPage => {
+                // Recovery share setup view
+                self.recovery_page.view()
+            }
+            AppState::EnvPage => {
+                // Encrypted env file editor
+                self.env_page.view()
+            }
+            AppState::HistoryPage => {
+                // Operations finished this session
+                self.history_page.view()
+            }
+            AppState::SettingsPage => {
+                // Preferences saved to config.toml
+                self.settings_page.view()
+            }
+        };
+
+        // Toasts sit below the page so they never cover its controls
+        let content = Column::new()
+            .height(Length::Fill)
+            .push(page)
+            .push(self.toasts.view());
+
+        Container::new(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(theme::PageContainer)
+            .into()
     }
 }
 
-// Main function that calls the iced front-end
+// Main function that calls the iced front-end, or a headless mode when given one
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(command) = args.first() {
+        let result = match command.as_str() {
+            #[cfg(unix)]
+            "daemon" => daemon::run(args.get(1).cloned()),
+            "git" if args.get(1).map(String::as_str) == Some("init") => gitfilter::init(&args[2..]),
+            "run" => envfile::run(&args[1..]),
+            "git-filter" => gitfilter::filter(args.get(1).map(String::as_str)),
+            _ => Err(format!("Unknown command: {}", command)),
+        };
+        if let Err(e) = result {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // Reopen the window where it was last closed, or at the configured size
+    let window = config::current().window;
+    let (size, position) = match session::current().window {
+        Some(last) => (
+            (last.width, last.height),
+            match (last.x, last.y) {
+                (Some(x), Some(y)) => iced::window::Position::Specific(x, y),
+                _ => iced::window::Position::default(),
+            },
+        ),
+        None => (
+            (window.width, window.height),
+            iced::window::Position::default(),
+        ),
+    };
     let settings = Settings {
         window: iced::window::Settings {
-            size: (800, 600), // Set the initial size (width, height)
+            size,
+            position,
             ..iced::window::Settings::default()
         },
+        // Close requests save the session before the app exits
+        exit_on_close_request: false,
         ..Settings::default()
     };
 
@@ -166,6 +452,9 @@ fn main() {
 /*
 COMMANDS
 - cargo run (run the project quickly)
+- cargo run -- daemon [username] (serve encrypt/decrypt requests over a Unix socket)
+- cargo run -- run --env <file.env.age> -- <command> (run a command with decrypted env vars)
+- cargo run -- git init [pattern...] (encrypt matching files in the current git repository)
 - cargo fmt (format the whole project)
 - cargo check (check the current project for errors without producing an executable)
 - cargo doc (build the documentation for the current project)
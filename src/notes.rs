@@ -0,0 +1,348 @@
+// src/notes.rs
+
+use crate::editor::Editor;
+use crate::store::{AlternateRowDark, BlueBorderContainer, BlueButton, GreenButton, OrangeButton};
+use crate::vault::Vault;
+use iced::{
+    alignment, button, scrollable, text_input, Alignment, Button, Color, Column, Container,
+    Element, Image, Length, Row, Scrollable, Text, TextInput,
+};
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Name of the vault record holding all notes
+const NOTES_RECORD: &str = "notes";
+
+// A single secure note
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Note {
+    id: u64,
+    title: String,
+    tags: Vec<String>,
+    body: String,
+    modified: u64,
+}
+
+impl Note {
+    // Case-insensitive match against title, tags and body
+    fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        self.title.to_lowercase().contains(&query)
+            || self.body.to_lowercase().contains(&query)
+            || self
+                .tags
+                .iter()
+                .any(|tag| tag.to_lowercase().contains(&query))
+    }
+}
+
+// Define the state of the notes page
+#[derive(Default)]
+pub struct NotesPage {
+    notes: Vec<Note>,
+    selected: Option<u64>,
+    search: String,
+    tags_text: String,
+    body: Editor,
+    dirty: bool,
+    status: Option<String>,
+    scroll: scrollable::State,
+    search_input: text_input::State,
+    title_input: text_input::State,
+    tags_input: text_input::State,
+    paste_button: button::State,
+    new_button: button::State,
+    delete_button: button::State,
+    back_button: button::State,
+    note_buttons: Vec<button::State>,
+}
+
+impl NotesPage {
+    // Load the notes of the unlocked vault
+    pub fn load(&mut self, vault: &Vault) {
+        *self = Self::default();
+        match vault.load(NOTES_RECORD) {
+            Ok(notes) => self.notes = notes,
+            Err(e) => self.status = Some(e),
+        }
+    }
+
+    // Whether there are edits waiting for the next autosave
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    // Encrypt and persist all notes if anything changed
+    pub fn save(&mut self, vault: &Vault) -> Result<(), String> {
+        if !self.dirty {
+            return Ok(());
+        }
+
+        match vault.save(NOTES_RECORD, &self.notes) {
+            Ok(()) => {
+                self.dirty = false;
+                self.status = Some("All changes saved".to_string());
+                Ok(())
+            }
+            Err(e) => {
+                self.status = Some(e.clone());
+                Err(e)
+            }
+        }
+    }
+
+    pub fn new_note(&mut self) {
+        let id = self.notes.iter().map(|note| note.id + 1).max().unwrap_or(0);
+        self.notes.push(Note {
+            id,
+            title: String::new(),
+            tags: Vec::new(),
+            body: String::new(),
+            modified: now(),
+        });
+        self.select_note(id);
+        self.dirty = true;
+    }
+
+    pub fn select_note(&mut self, id: u64) {
+        self.selected = Some(id);
+        self.tags_text = self
+            .selected_note()
+            .map(|note| note.tags.join(", "))
+            .unwrap_or_default();
+        self.body = Editor::new(
+            self.selected_note()
+                .map(|note| note.body.as_str())
+                .unwrap_or_default(),
+        );
+    }
+
+    pub fn delete_note(&mut self) {
+        if let Some(id) = self.selected.take() {
+            self.notes.retain(|note| note.id != id);
+            self.tags_text.clear();
+            self.body = Editor::default();
+            self.dirty = true;
+        }
+    }
+
+    pub fn search_changed(&mut self, value: String) {
+        self.search = value;
+    }
+
+    pub fn title_changed(&mut self, value: String) {
+        self.edit_note(|note| note.title = value);
+    }
+
+    pub fn tags_changed(&mut self, value: String) {
+        let tags = value
+            .split(',')
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+        self.tags_text = value;
+        self.edit_note(|note| note.tags = tags);
+    }
+
+    pub fn body_edited(&mut self, index: usize, value: String) {
+        if self.body.edit(index, value) {
+            self.sync_body();
+        }
+    }
+
+    pub fn body_new_line(&mut self, index: usize) {
+        self.body.split(index);
+        self.sync_body();
+    }
+
+    pub fn body_pasted(&mut self, text: &str) {
+        self.body.paste(text);
+        self.sync_body();
+    }
+
+    pub fn move_body_line(&mut self, down: bool) {
+        self.body.move_focus(down);
+    }
+
+    fn sync_body(&mut self) {
+        let body = self.body.text();
+        self.edit_note(|note| note.body = body);
+    }
+
+    fn selected_note(&self) -> Option<&Note> {
+        let id = self.selected?;
+        self.notes.iter().find(|note| note.id == id)
+    }
+
+    // Apply an edit to the selected note and mark it for autosave
+    fn edit_note<F: FnOnce(&mut Note)>(&mut self, edit: F) {
+        let id = match self.selected {
+            Some(id) => id,
+            None => return,
+        };
+
+        if let Some(note) = self.notes.iter_mut().find(|note| note.id == id) {
+            edit(note);
+            note.modified = now();
+            self.dirty = true;
+            self.status = Some("Unsaved changes".to_string());
+        }
+    }
+
+    pub fn view(&mut self) -> Element<'_, crate::Message> {
+        let logo = Container::new(
+            Image::new("images/logo.png")
+                .width(Length::Units(40))
+                .height(Length::Units(40)),
+        )
+        .padding(10)
+        .align_x(alignment::Horizontal::Center);
+
+        let search_input = TextInput::new(
+            &mut self.search_input,
+            "search notes",
+            &self.search,
+            crate::Message::NoteSearchChanged,
+        )
+        .padding(10)
+        .size(16)
+        .width(Length::Units(220));
+
+        // Keep one button state per note so the list stays clickable
+        self.note_buttons
+            .resize_with(self.notes.len(), button::State::new);
+
+        let mut note_list = Scrollable::new(&mut self.scroll)
+            .spacing(5)
+            .height(Length::Units(300))
+            .width(Length::Units(220));
+        for (note, state) in self.notes.iter().zip(self.note_buttons.iter_mut()) {
+            if !self.search.is_empty() && !note.matches(&self.search) {
+                continue;
+            }
+
+            let title = if note.title.is_empty() {
+                "Untitled".to_string()
+            } else {
+                note.title.clone()
+            };
+            let mut note_button = Button::new(state, Text::new(title).size(16))
+                .width(Length::Fill)
+                .on_press(crate::Message::SelectNote(note.id));
+            if self.selected == Some(note.id) {
+                note_button = note_button.style(BlueButton);
+            }
+            note_list = note_list.push(note_button);
+        }
+
+        let new_button = Button::new(&mut self.new_button, Text::new("New Note").size(20))
+            .style(GreenButton)
+            .on_press(crate::Message::NewNote);
+
+        let list_column = Column::new()
+            .spacing(10)
+            .push(search_input)
+            .push(Container::new(note_list).style(AlternateRowDark))
+            .push(new_button);
+
+        let selected = self.selected;
+        let editor: Element<crate::Message> = match selected
+            .and_then(|id| self.notes.iter().find(|note| note.id == id))
+        {
+            Some(note) => {
+                let title_input = TextInput::new(
+                    &mut self.title_input,
+                    "title",
+                    &note.title,
+                    crate::Message::NoteTitleChanged,
+                )
+                .padding(10)
+                .size(20)
+                .width(Length::Units(400));
+
+                let tags_input = TextInput::new(
+                    &mut self.tags_input,
+                    "tags, separated by commas",
+                    &self.tags_text,
+                    crate::Message::NoteTagsChanged,
+                )
+                .padding(10)
+                .size(16)
+                .width(Length::Units(400));
+
+                let body_input = Container::new(self.body.view(
+                    "note",
+                    200,
+                    crate::Message::NoteBodyEdited,
+                    crate::Message::NoteBodyNewLine,
+                ))
+                .width(Length::Units(400));
+
+                // Pasting through the clipboard keeps line breaks
+                let paste_button = Button::new(&mut self.paste_button, Text::new("Paste").size(20))
+                    .style(BlueButton)
+                    .on_press(crate::Message::PasteNoteBody);
+
+                let delete_button =
+                    Button::new(&mut self.delete_button, Text::new("Delete").size(20))
+                        .style(OrangeButton)
+                        .on_press(crate::Message::DeleteNote);
+
+                Column::new()
+                    .spacing(10)
+                    .push(title_input)
+                    .push(tags_input)
+                    .push(body_input)
+                    .push(
+                        Row::new()
+                            .spacing(10)
+                            .push(paste_button)
+                            .push(delete_button),
+                    )
+                    .into()
+            }
+            None => Text::new("Select a note or create a new one")
+                .size(18)
+                .into(),
+        };
+
+        let back_button = Button::new(&mut self.back_button, Text::new("Back").size(16))
+            .style(BlueButton)
+            .on_press(crate::Message::SwitchToStorePage);
+
+        let mut content = Column::new()
+            .spacing(20)
+            .align_items(Alignment::Center)
+            .push(Text::new("Secure Notes").size(24))
+            .push(
+                Container::new(Row::new().spacing(20).push(list_column).push(editor))
+                    .style(BlueBorderContainer)
+                    .padding(10),
+            );
+
+        if let Some(status) = &self.status {
+            content = content.push(
+                Text::new(status)
+                    .size(14)
+                    .color(Color::from_rgb(0.5, 0.5, 0.5)),
+            );
+        }
+        content = content.push(back_button);
+
+        let container = Container::new(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y();
+
+        Column::new().push(logo).push(container).into()
+    }
+}
+
+// Seconds since the Unix epoch
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
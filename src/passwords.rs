@@ -0,0 +1,737 @@
+// src/passwords.rs
+
+use crate::generator::{self, CharClass, GeneratorOptions};
+use crate::import::{ImportAction, ImportFormat, ImportPanel};
+use crate::store::{AlternateRowDark, BlueBorderContainer, BlueButton, GreenButton, OrangeButton};
+use crate::vault::Vault;
+use iced::{
+    alignment, button, scrollable, slider, text_input, Alignment, Button, Checkbox, Color, Column,
+    Container, Element, Image, Length, Row, Scrollable, Slider, Text, TextInput,
+};
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Name of the vault record holding all password entries
+const PASSWORDS_RECORD: &str = "passwords";
+
+// Editable fields of a password entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryField {
+    Site,
+    Username,
+    Password,
+    Url,
+    Notes,
+}
+
+// A user-defined name/value pair attached to an entry
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct CustomField {
+    pub name: String,
+    pub value: String,
+}
+
+// A single credential stored in the vault
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct Entry {
+    pub id: u64,
+    pub site: String,
+    pub username: String,
+    pub password: String,
+    pub url: String,
+    pub notes: String,
+    pub custom_fields: Vec<CustomField>,
+    pub modified: u64,
+}
+
+impl Entry {
+    pub fn field(&self, field: EntryField) -> &str {
+        match field {
+            EntryField::Site => &self.site,
+            EntryField::Username => &self.username,
+            EntryField::Password => &self.password,
+            EntryField::Url => &self.url,
+            EntryField::Notes => &self.notes,
+        }
+    }
+
+    fn field_mut(&mut self, field: EntryField) -> &mut String {
+        match field {
+            EntryField::Site => &mut self.site,
+            EntryField::Username => &mut self.username,
+            EntryField::Password => &mut self.password,
+            EntryField::Url => &mut self.url,
+            EntryField::Notes => &mut self.notes,
+        }
+    }
+
+    // Case-insensitive match against site, username and URL
+    fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        [&self.site, &self.username, &self.url]
+            .iter()
+            .any(|value| value.to_lowercase().contains(&query))
+    }
+}
+
+// Widget states for one custom field row
+#[derive(Default)]
+struct CustomFieldState {
+    name_input: text_input::State,
+    value_input: text_input::State,
+    remove_button: button::State,
+}
+
+// Define the state of the password vault page
+#[derive(Default)]
+pub struct PasswordsPage {
+    entries: Vec<Entry>,
+    selected: Option<u64>,
+    search: String,
+    password_revealed: bool,
+    dirty: bool,
+    status: Option<String>,
+    generator: GeneratorOptions,
+    generated: String,
+    import: ImportPanel,
+    show_import: bool,
+    scroll: scrollable::State,
+    editor_scroll: scrollable::State,
+    search_input: text_input::State,
+    site_input: text_input::State,
+    username_input: text_input::State,
+    password_input: text_input::State,
+    url_input: text_input::State,
+    notes_input: text_input::State,
+    new_button: button::State,
+    delete_button: button::State,
+    back_button: button::State,
+    reveal_button: button::State,
+    copy_username_button: button::State,
+    copy_password_button: button::State,
+    add_field_button: button::State,
+    mode_button: button::State,
+    generate_button: button::State,
+    use_button: button::State,
+    import_button: button::State,
+    length_slider: slider::State,
+    entry_buttons: Vec<button::State>,
+    field_states: Vec<CustomFieldState>,
+}
+
+impl PasswordsPage {
+    // Load the entries of the unlocked vault
+    pub fn load(&mut self, vault: &Vault) {
+        *self = Self::default();
+        match vault.load(PASSWORDS_RECORD) {
+            Ok(entries) => self.entries = entries,
+            Err(e) => self.status = Some(e),
+        }
+        self.generate();
+    }
+
+    // Whether there are edits waiting for the next autosave
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    // Encrypt and persist all entries if anything changed
+    pub fn save(&mut self, vault: &Vault) -> Result<(), String> {
+        if !self.dirty {
+            return Ok(());
+        }
+
+        match vault.save(PASSWORDS_RECORD, &self.entries) {
+            Ok(()) => {
+                self.dirty = false;
+                self.status = Some("All changes saved".to_string());
+                Ok(())
+            }
+            Err(e) => {
+                self.status = Some(e.clone());
+                Err(e)
+            }
+        }
+    }
+
+    pub fn new_entry(&mut self) {
+        let id = self
+            .entries
+            .iter()
+            .map(|entry| entry.id + 1)
+            .max()
+            .unwrap_or(0);
+        self.entries.push(Entry {
+            id,
+            modified: now(),
+            ..Entry::default()
+        });
+        self.select_entry(id);
+        self.dirty = true;
+    }
+
+    pub fn select_entry(&mut self, id: u64) {
+        self.selected = Some(id);
+        self.password_revealed = false;
+    }
+
+    pub fn delete_entry(&mut self) {
+        if let Some(id) = self.selected.take() {
+            self.entries.retain(|entry| entry.id != id);
+            self.dirty = true;
+        }
+    }
+
+    pub fn search_changed(&mut self, value: String) {
+        self.search = value;
+    }
+
+    pub fn field_changed(&mut self, field: EntryField, value: String) {
+        self.edit_entry(|entry| *entry.field_mut(field) = value);
+    }
+
+    pub fn add_custom_field(&mut self) {
+        self.edit_entry(|entry| entry.custom_fields.push(CustomField::default()));
+    }
+
+    pub fn remove_custom_field(&mut self, index: usize) {
+        self.edit_entry(|entry| {
+            if index < entry.custom_fields.len() {
+                entry.custom_fields.remove(index);
+            }
+        });
+    }
+
+    pub fn custom_field_name_changed(&mut self, index: usize, value: String) {
+        self.edit_entry(|entry| {
+            if let Some(field) = entry.custom_fields.get_mut(index) {
+                field.name = value;
+            }
+        });
+    }
+
+    pub fn custom_field_value_changed(&mut self, index: usize, value: String) {
+        self.edit_entry(|entry| {
+            if let Some(field) = entry.custom_fields.get_mut(index) {
+                field.value = value;
+            }
+        });
+    }
+
+    pub fn toggle_reveal_password(&mut self) {
+        self.password_revealed = !self.password_revealed;
+    }
+
+    // Value of a field of the selected entry, for copying to the clipboard
+    pub fn selected_field(&self, field: EntryField) -> Option<String> {
+        self.selected_entry()
+            .map(|entry| entry.field(field).to_string())
+            .filter(|value| !value.is_empty())
+    }
+
+    pub fn toggle_generator_mode(&mut self) {
+        self.generator.passphrase = !self.generator.passphrase;
+        self.generate();
+    }
+
+    pub fn generator_length_changed(&mut self, value: u8) {
+        if self.generator.passphrase {
+            self.generator.words = value;
+        } else {
+            self.generator.length = value;
+        }
+        self.generate();
+    }
+
+    pub fn generator_class_toggled(&mut self, class: CharClass, enabled: bool) {
+        self.generator.set_class(class, enabled);
+        self.generate();
+    }
+
+    pub fn generate(&mut self) {
+        match generator::generate(&self.generator) {
+            Ok(generated) => self.generated = generated,
+            Err(e) => {
+                self.generated.clear();
+                self.status = Some(e);
+            }
+        }
+    }
+
+    // Use the generated password for the selected entry
+    pub fn use_generated(&mut self) {
+        if self.generated.is_empty() {
+            return;
+        }
+        let generated = self.generated.clone();
+        self.edit_entry(|entry| entry.password = generated);
+    }
+
+    pub fn toggle_import(&mut self) {
+        self.show_import = !self.show_import;
+        self.import = ImportPanel::default();
+    }
+
+    pub fn choose_import_source(&mut self, format: ImportFormat) {
+        self.import.choose_source(format);
+    }
+
+    pub fn choose_import_keyfile(&mut self) {
+        self.import.choose_keyfile();
+    }
+
+    pub fn import_password_changed(&mut self, value: String) {
+        self.import.password_changed(value);
+    }
+
+    pub fn overwrite_conflicts_toggled(&mut self, overwrite: bool) {
+        self.import.overwrite_toggled(overwrite);
+    }
+
+    pub fn preview_import(&mut self) {
+        self.import.preview(&self.entries);
+    }
+
+    // Write the previewed entries into the vault
+    pub fn confirm_import(&mut self) {
+        let accepted = self.import.take_accepted();
+        let mut next_id = self
+            .entries
+            .iter()
+            .map(|entry| entry.id + 1)
+            .max()
+            .unwrap_or(0);
+        let count = accepted.len();
+
+        for candidate in accepted {
+            let mut entry = candidate.entry;
+            entry.modified = now();
+            // A conflicting entry deleted since the preview is added back as new
+            let existing = match candidate.action {
+                ImportAction::Conflict(id) => self.entries.iter_mut().find(|e| e.id == id),
+                _ => None,
+            };
+            match existing {
+                Some(existing) => {
+                    entry.id = existing.id;
+                    *existing = entry;
+                }
+                None => {
+                    entry.id = next_id;
+                    next_id += 1;
+                    self.entries.push(entry);
+                }
+            }
+        }
+
+        self.show_import = false;
+        self.dirty = true;
+        self.status = Some(format!("Imported {} entries", count));
+    }
+
+    fn selected_entry(&self) -> Option<&Entry> {
+        let id = self.selected?;
+        self.entries.iter().find(|entry| entry.id == id)
+    }
+
+    // Apply an edit to the selected entry and mark it for autosave
+    fn edit_entry<F: FnOnce(&mut Entry)>(&mut self, edit: F) {
+        let id = match self.selected {
+            Some(id) => id,
+            None => return,
+        };
+
+        if let Some(entry) = self.entries.iter_mut().find(|entry| entry.id == id) {
+            edit(entry);
+            entry.modified = now();
+            self.dirty = true;
+            self.status = Some("Unsaved changes".to_string());
+        }
+    }
+
+    pub fn view(&mut self) -> Element<'_, crate::Message> {
+        let logo = Container::new(
+            Image::new("images/logo.png")
+                .width(Length::Units(40))
+                .height(Length::Units(40)),
+        )
+        .padding(10)
+        .align_x(alignment::Horizontal::Center);
+
+        let search_input = TextInput::new(
+            &mut self.search_input,
+            "search entries",
+            &self.search,
+            crate::Message::EntrySearchChanged,
+        )
+        .padding(10)
+        .size(16)
+        .width(Length::Units(200));
+
+        // Keep one button state per entry so the list stays clickable
+        self.entry_buttons
+            .resize_with(self.entries.len(), button::State::new);
+
+        let mut entry_list = Scrollable::new(&mut self.scroll)
+            .spacing(5)
+            .height(Length::Units(300))
+            .width(Length::Units(200));
+        for (entry, state) in self.entries.iter().zip(self.entry_buttons.iter_mut()) {
+            if !self.search.is_empty() && !entry.matches(&self.search) {
+                continue;
+            }
+
+            let site = if entry.site.is_empty() {
+                "Untitled".to_string()
+            } else {
+                entry.site.clone()
+            };
+            let mut entry_button = Button::new(state, Text::new(site).size(16))
+                .width(Length::Fill)
+                .on_press(crate::Message::SelectEntry(entry.id));
+            if self.selected == Some(entry.id) {
+                entry_button = entry_button.style(BlueButton);
+            }
+            entry_list = entry_list.push(entry_button);
+        }
+
+        let new_button = Button::new(&mut self.new_button, Text::new("New Entry").size(20))
+            .style(GreenButton)
+            .on_press(crate::Message::NewEntry);
+
+        let import_button = Button::new(
+            &mut self.import_button,
+            Text::new(if self.show_import {
+                "Generator"
+            } else {
+                "Import"
+            })
+            .size(20),
+        )
+        .style(BlueButton)
+        .on_press(crate::Message::ToggleImportPanel);
+
+        let list_column = Column::new()
+            .spacing(10)
+            .push(search_input)
+            .push(Container::new(entry_list).style(AlternateRowDark))
+            .push(Row::new().spacing(10).push(new_button).push(import_button));
+
+        // The import panel takes the generator's place while it is open
+        let side_panel = if self.show_import {
+            self.import.view()
+        } else {
+            Self::generator_view(
+                &self.generator,
+                &self.generated,
+                &mut self.mode_button,
+                &mut self.generate_button,
+                &mut self.use_button,
+                &mut self.length_slider,
+            )
+        };
+
+        let selected = self.selected;
+        let editor: Element<crate::Message> = match selected
+            .and_then(|id| self.entries.iter().find(|entry| entry.id == id))
+        {
+            Some(entry) => {
+                let password_input = TextInput::new(
+                    &mut self.password_input,
+                    "password",
+                    &entry.password,
+                    |value| crate::Message::EntryFieldChanged(EntryField::Password, value),
+                )
+                .padding(8)
+                .size(16)
+                .width(Length::Units(220));
+                let password_input = if self.password_revealed {
+                    password_input
+                } else {
+                    password_input.password()
+                };
+
+                let strength =
+                    generator::strength(&entry.password, &[&entry.site, &entry.username]);
+                let strength_text = match &strength.feedback {
+                    Some(feedback) if !entry.password.is_empty() => {
+                        format!("Strength: {} - {}", strength.label(), feedback)
+                    }
+                    _ => format!("Strength: {}", strength.label()),
+                };
+
+                let password_row = Row::new()
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .push(password_input)
+                    .push(
+                        Button::new(
+                            &mut self.reveal_button,
+                            Text::new(if self.password_revealed {
+                                "Hide"
+                            } else {
+                                "Show"
+                            })
+                            .size(16),
+                        )
+                        .style(BlueButton)
+                        .on_press(crate::Message::ToggleRevealPassword),
+                    )
+                    .push(
+                        Button::new(&mut self.copy_password_button, Text::new("Copy").size(16))
+                            .style(BlueButton)
+                            .on_press(crate::Message::CopyEntryField(EntryField::Password)),
+                    );
+
+                let username_row = Row::new()
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .push(
+                        TextInput::new(
+                            &mut self.username_input,
+                            "username",
+                            &entry.username,
+                            |value| crate::Message::EntryFieldChanged(EntryField::Username, value),
+                        )
+                        .padding(8)
+                        .size(16)
+                        .width(Length::Units(220)),
+                    )
+                    .push(
+                        Button::new(&mut self.copy_username_button, Text::new("Copy").size(16))
+                            .style(BlueButton)
+                            .on_press(crate::Message::CopyEntryField(EntryField::Username)),
+                    );
+
+                let mut fields = Column::new()
+                    .spacing(10)
+                    .push(
+                        TextInput::new(&mut self.site_input, "site", &entry.site, |value| {
+                            crate::Message::EntryFieldChanged(EntryField::Site, value)
+                        })
+                        .padding(8)
+                        .size(16)
+                        .width(Length::Units(300)),
+                    )
+                    .push(username_row)
+                    .push(password_row)
+                    .push(Text::new(strength_text).size(14))
+                    .push(
+                        TextInput::new(&mut self.url_input, "URL", &entry.url, |value| {
+                            crate::Message::EntryFieldChanged(EntryField::Url, value)
+                        })
+                        .padding(8)
+                        .size(16)
+                        .width(Length::Units(300)),
+                    )
+                    .push(
+                        TextInput::new(&mut self.notes_input, "notes", &entry.notes, |value| {
+                            crate::Message::EntryFieldChanged(EntryField::Notes, value)
+                        })
+                        .padding(8)
+                        .size(16)
+                        .width(Length::Units(300)),
+                    );
+
+                // Keep one set of widget states per custom field
+                self.field_states
+                    .resize_with(entry.custom_fields.len(), CustomFieldState::default);
+                for (index, (field, state)) in entry
+                    .custom_fields
+                    .iter()
+                    .zip(self.field_states.iter_mut())
+                    .enumerate()
+                {
+                    fields = fields.push(
+                        Row::new()
+                            .spacing(10)
+                            .align_items(Alignment::Center)
+                            .push(
+                                TextInput::new(
+                                    &mut state.name_input,
+                                    "name",
+                                    &field.name,
+                                    move |value| {
+                                        crate::Message::CustomFieldNameChanged(index, value)
+                                    },
+                                )
+                                .padding(8)
+                                .size(16)
+                                .width(Length::Units(120)),
+                            )
+                            .push(
+                                TextInput::new(
+                                    &mut state.value_input,
+                                    "value",
+                                    &field.value,
+                                    move |value| {
+                                        crate::Message::CustomFieldValueChanged(index, value)
+                                    },
+                                )
+                                .padding(8)
+                                .size(16)
+                                .width(Length::Units(150)),
+                            )
+                            .push(
+                                Button::new(&mut state.remove_button, Text::new("Remove").size(14))
+                                    .style(OrangeButton)
+                                    .on_press(crate::Message::RemoveCustomField(index)),
+                            ),
+                    );
+                }
+
+                let actions = Row::new()
+                    .spacing(10)
+                    .push(
+                        Button::new(&mut self.add_field_button, Text::new("Add Field").size(16))
+                            .style(BlueButton)
+                            .on_press(crate::Message::AddCustomField),
+                    )
+                    .push(
+                        Button::new(&mut self.delete_button, Text::new("Delete").size(16))
+                            .style(OrangeButton)
+                            .on_press(crate::Message::DeleteEntry),
+                    );
+
+                Scrollable::new(&mut self.editor_scroll)
+                    .height(Length::Units(360))
+                    .push(fields.push(actions))
+                    .into()
+            }
+            None => Text::new("Select an entry or create a new one")
+                .size(18)
+                .into(),
+        };
+
+        let back_button = Button::new(&mut self.back_button, Text::new("Back").size(16))
+            .style(BlueButton)
+            .on_press(crate::Message::SwitchToStorePage);
+
+        let mut content = Column::new()
+            .spacing(20)
+            .align_items(Alignment::Center)
+            .push(Text::new("Password Vault").size(24))
+            .push(
+                Container::new(
+                    Row::new()
+                        .spacing(20)
+                        .push(list_column)
+                        .push(editor)
+                        .push(side_panel),
+                )
+                .style(BlueBorderContainer)
+                .padding(10),
+            );
+
+        if let Some(status) = &self.status {
+            content = content.push(
+                Text::new(status)
+                    .size(14)
+                    .color(Color::from_rgb(0.5, 0.5, 0.5)),
+            );
+        }
+        content = content.push(back_button);
+
+        let container = Container::new(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y();
+
+        Column::new().push(logo).push(container).into()
+    }
+
+    // Builds the password generator panel
+    fn generator_view<'a>(
+        options: &GeneratorOptions,
+        generated: &str,
+        mode_button: &'a mut button::State,
+        generate_button: &'a mut button::State,
+        use_button: &'a mut button::State,
+        length_slider: &'a mut slider::State,
+    ) -> Element<'a, crate::Message> {
+        let (length_label, range, value) = if options.passphrase {
+            (format!("Words: {}", options.words), 3..=12, options.words)
+        } else {
+            (
+                format!("Length: {}", options.length),
+                8..=64,
+                options.length,
+            )
+        };
+
+        let mut generator = Column::new()
+            .spacing(10)
+            .width(Length::Units(220))
+            .push(Text::new("Generator").size(20))
+            .push(
+                Button::new(
+                    mode_button,
+                    Text::new(if options.passphrase {
+                        "Passphrase"
+                    } else {
+                        "Password"
+                    })
+                    .size(16),
+                )
+                .style(BlueButton)
+                .on_press(crate::Message::ToggleGeneratorMode),
+            )
+            .push(Text::new(length_label).size(16))
+            .push(Slider::new(
+                length_slider,
+                range,
+                value,
+                crate::Message::GeneratorLengthChanged,
+            ));
+
+        if !options.passphrase {
+            for (class, label) in [
+                (CharClass::Lowercase, "a-z"),
+                (CharClass::Uppercase, "A-Z"),
+                (CharClass::Digits, "0-9"),
+                (CharClass::Symbols, "!@#"),
+            ] {
+                generator = generator.push(
+                    Checkbox::new(options.class_enabled(class), label, move |enabled| {
+                        crate::Message::GeneratorClassToggled(class, enabled)
+                    })
+                    .size(16),
+                );
+            }
+        }
+
+        let strength = generator::strength(generated, &[]);
+        generator
+            .push(
+                Container::new(Text::new(generated).size(14))
+                    .style(AlternateRowDark)
+                    .padding(5)
+                    .width(Length::Fill),
+            )
+            .push(Text::new(format!("Strength: {}", strength.label())).size(14))
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .push(
+                        Button::new(generate_button, Text::new("Generate").size(16))
+                            .style(GreenButton)
+                            .on_press(crate::Message::GeneratePassword),
+                    )
+                    .push(
+                        Button::new(use_button, Text::new("Use").size(16))
+                            .style(BlueButton)
+                            .on_press(crate::Message::UseGeneratedPassword),
+                    ),
+            )
+            .into()
+    }
+}
+
+// Seconds since the Unix epoch
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
@@ -0,0 +1,174 @@
+// src/preview.rs
+
+use crate::store::{AlternateRowDark, BlueBorderContainer, BlueButton};
+use iced::{
+    button, image, scrollable, Alignment, Button, Column, Container, Element, Font, Image, Length,
+    Scrollable, Text,
+};
+use zeroize::Zeroize;
+
+// Monospaced font for text and hex previews
+const MONOSPACE: Font = Font::External {
+    name: "DejaVu Sans Mono",
+    bytes: include_bytes!("../assets/DejaVuSansMono.ttf"),
+};
+
+// Only the start of large binaries is shown as hex
+const HEX_LIMIT: usize = 64 * 1024;
+
+// Larger files are not decrypted into memory for a preview
+pub const MAX_SIZE: u64 = 16 * 1024 * 1024;
+
+// How decrypted bytes are shown
+enum PreviewContent {
+    Text(String),
+    Image(image::Handle),
+    Hex(String),
+}
+
+// Decrypted file contents held in memory only, wiped when dropped
+pub struct Preview {
+    filename: String,
+    bytes: Vec<u8>,
+    content: PreviewContent,
+    scroll: scrollable::State,
+    close_button: button::State,
+}
+
+impl Preview {
+    pub fn new(filename: String, bytes: Vec<u8>) -> Self {
+        let content = if is_image(&bytes) {
+            PreviewContent::Image(image::Handle::from_memory(bytes.clone()))
+        } else {
+            match std::str::from_utf8(&bytes) {
+                Ok(text) if !text.contains('\0') => PreviewContent::Text(text.to_string()),
+                _ => PreviewContent::Hex(hex_dump(&bytes)),
+            }
+        };
+
+        Self {
+            filename,
+            bytes,
+            content,
+            scroll: scrollable::State::new(),
+            close_button: button::State::new(),
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self.content {
+            PreviewContent::Text(_) => "text",
+            PreviewContent::Image(_) => "image",
+            PreviewContent::Hex(_) => "binary",
+        }
+    }
+
+    pub fn view(&mut self) -> Element<'_, crate::Message> {
+        let body: Element<crate::Message> = match &self.content {
+            PreviewContent::Text(text) => Text::new(text.as_str()).font(MONOSPACE).size(14).into(),
+            PreviewContent::Image(handle) => Image::new(handle.clone()).into(),
+            PreviewContent::Hex(dump) => Text::new(dump.as_str()).font(MONOSPACE).size(14).into(),
+        };
+
+        let kind = self.kind();
+
+        let scroll = Scrollable::new(&mut self.scroll)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .push(Container::new(body).padding(10));
+
+        Column::new()
+            .spacing(10)
+            .align_items(Alignment::Center)
+            .width(Length::Units(700))
+            .height(Length::Units(500))
+            .push(Text::new(format!("Preview of {} ({})", self.filename, kind)).size(24))
+            .push(
+                Container::new(Container::new(scroll).style(AlternateRowDark))
+                    .style(BlueBorderContainer)
+                    .padding(5),
+            )
+            .push(
+                Button::new(&mut self.close_button, Text::new("Close Preview").size(16))
+                    .style(BlueButton)
+                    .on_press(crate::Message::ClosePreview),
+            )
+            .into()
+    }
+}
+
+impl Drop for Preview {
+    // The image handle keeps its own copy inside iced, which cannot be wiped here
+    fn drop(&mut self) {
+        self.bytes.zeroize();
+        match &mut self.content {
+            PreviewContent::Text(text) | PreviewContent::Hex(text) => text.zeroize(),
+            PreviewContent::Image(_) => {}
+        }
+    }
+}
+
+// Recognise the image formats iced can decode by their magic bytes
+fn is_image(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"\x89PNG\r\n\x1a\n")
+        || bytes.starts_with(b"\xff\xd8\xff")
+        || bytes.starts_with(b"GIF87a")
+        || bytes.starts_with(b"GIF89a")
+        || bytes.starts_with(b"BM")
+        || (bytes.len() > 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP")
+}
+
+// Classic offset / hex / ASCII dump
+fn hex_dump(bytes: &[u8]) -> String {
+    let mut dump = String::new();
+    for (index, line) in bytes[..bytes.len().min(HEX_LIMIT)].chunks(16).enumerate() {
+        let hex: Vec<String> = line.iter().map(|byte| format!("{:02x}", byte)).collect();
+        let ascii: String = line
+            .iter()
+            .map(|&byte| {
+                if byte.is_ascii_graphic() || byte == b' ' {
+                    byte as char
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        dump.push_str(&format!(
+            "{:08x}  {:<47}  {}\n",
+            index * 16,
+            hex.join(" "),
+            ascii
+        ));
+    }
+    if bytes.len() > HEX_LIMIT {
+        dump.push_str(&format!("... {} more bytes\n", bytes.len() - HEX_LIMIT));
+    }
+    dump
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kind(bytes: &[u8]) -> &'static str {
+        Preview::new("file".to_string(), bytes.to_vec()).kind()
+    }
+
+    #[test]
+    fn classifies_contents() {
+        assert_eq!(kind(b"hello\nworld"), "text");
+        assert_eq!(kind("h\u{e9}llo".as_bytes()), "text");
+        assert_eq!(kind(b"\x89PNG\r\n\x1a\n...."), "image");
+        assert_eq!(kind(b"\xff\xd8\xff\xe0...."), "image");
+        assert_eq!(kind(b"RIFF\0\0\0\0WEBPVP8 "), "image");
+        assert_eq!(kind(b"text\0with a nul"), "binary");
+        assert_eq!(kind(&[0xc3, 0x28, 0xff]), "binary");
+    }
+
+    #[test]
+    fn hex_dump_is_capped() {
+        let dump = hex_dump(&vec![0u8; HEX_LIMIT * 2]);
+        assert_eq!(dump.lines().count(), HEX_LIMIT / 16 + 1);
+        assert!(dump.starts_with("00000000"));
+    }
+}
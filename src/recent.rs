@@ -0,0 +1,290 @@
+// src/recent.rs
+
+use crate::store::{AlternateRowDark, BlueButton, OrangeButton};
+use crate::vault::Vault;
+use iced::{
+    button, scrollable, Alignment, Button, Color, Column, Container, Element, Length, Row,
+    Scrollable, Text,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Vault record holding recent operations and favorites
+const RECENT_RECORD: &str = "recent";
+
+// Number of recent operations kept
+const RECENT_LIMIT: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action {
+    Encrypted,
+    Decrypted,
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Action::Encrypted => "Encrypted",
+            Action::Decrypted => "Decrypted",
+        })
+    }
+}
+
+// One encrypt or decrypt, with the error when it failed
+#[derive(Clone, Serialize, Deserialize)]
+struct Operation {
+    path: PathBuf,
+    action: Action,
+    timestamp: u64,
+    error: Option<String>,
+}
+
+// Persisted per user in the vault
+#[derive(Default, Serialize, Deserialize)]
+struct RecentFiles {
+    operations: VecDeque<Operation>,
+    favorites: Vec<PathBuf>,
+}
+
+// Define the state of the recent files and favorites panel on the store page
+#[derive(Default)]
+pub struct RecentPanel {
+    files: RecentFiles,
+    // Paths that no longer exist, checked when the list changes or the panel opens
+    stale: HashSet<PathBuf>,
+    status: Option<String>,
+    scroll: scrollable::State,
+    favorite_buttons: Vec<(button::State, button::State)>,
+    operation_buttons: Vec<(button::State, button::State)>,
+    clear_button: button::State,
+}
+
+impl RecentPanel {
+    pub fn load(&mut self, vault: &Vault) {
+        *self = Self::default();
+        match vault.load(RECENT_RECORD) {
+            Ok(files) => self.files = files,
+            Err(e) => self.status = Some(e),
+        }
+        self.refresh();
+    }
+
+    pub fn save(&mut self, vault: &Vault) {
+        if let Err(e) = vault.save(RECENT_RECORD, &self.files) {
+            self.status = Some(e);
+        }
+    }
+
+    // Mark entries whose file was moved or deleted
+    pub fn refresh(&mut self) {
+        self.stale = self
+            .files
+            .operations
+            .iter()
+            .map(|operation| &operation.path)
+            .chain(&self.files.favorites)
+            .filter(|path| !path.exists())
+            .cloned()
+            .collect();
+    }
+
+    pub fn is_stale(&self, path: &Path) -> bool {
+        self.stale.contains(path)
+    }
+
+    pub fn record(&mut self, path: &Path, action: Action, result: &Result<(), String>) {
+        self.files.operations.push_front(Operation {
+            path: path.to_path_buf(),
+            action,
+            timestamp: now(),
+            error: result.as_ref().err().cloned(),
+        });
+        self.files.operations.truncate(RECENT_LIMIT);
+        self.refresh();
+    }
+
+    pub fn is_favorite(&self, path: &Path) -> bool {
+        self.files.favorites.iter().any(|favorite| favorite == path)
+    }
+
+    pub fn pin(&mut self, path: PathBuf) {
+        if !self.is_favorite(&path) {
+            self.files.favorites.push(path);
+            self.refresh();
+        }
+    }
+
+    pub fn unpin(&mut self, path: &Path) {
+        self.files.favorites.retain(|favorite| favorite != path);
+    }
+
+    pub fn clear(&mut self) {
+        self.files.operations.clear();
+        self.refresh();
+    }
+
+    pub fn view(&mut self) -> Element<'_, crate::Message> {
+        let stale = &self.stale;
+        let favorites = &self.files.favorites;
+
+        // Keep one pair of button states per row so the lists stay clickable
+        self.favorite_buttons
+            .resize_with(favorites.len(), Default::default);
+        self.operation_buttons
+            .resize_with(self.files.operations.len(), Default::default);
+
+        let mut list = Scrollable::new(&mut self.scroll)
+            .spacing(5)
+            .height(Length::Units(300))
+            .push(Text::new("Favorites").size(18));
+        if favorites.is_empty() {
+            list = list.push(hint("Pin a file to keep it here."));
+        }
+        for (path, (select_state, unpin_state)) in
+            favorites.iter().zip(self.favorite_buttons.iter_mut())
+        {
+            list = list.push(file_row(
+                select_state,
+                unpin_state,
+                path,
+                file_label(path),
+                "Unpin",
+                crate::Message::UnpinFile(path.clone()),
+                stale.contains(path),
+            ));
+        }
+
+        list = list.push(Text::new("Recent").size(18));
+        if self.files.operations.is_empty() {
+            list = list.push(hint("Files you encrypt or decrypt appear here."));
+        }
+        for (operation, (select_state, pin_state)) in self
+            .files
+            .operations
+            .iter()
+            .zip(self.operation_buttons.iter_mut())
+        {
+            let pinned = favorites.contains(&operation.path);
+            let result = match &operation.error {
+                Some(e) => format!("failed: {}", e),
+                None => "ok".to_string(),
+            };
+            let label = format!(
+                "{} {} - {}, {}",
+                operation.action,
+                file_label(&operation.path),
+                ago(operation.timestamp),
+                result
+            );
+            let (pin_label, pin_message) = if pinned {
+                ("Unpin", crate::Message::UnpinFile(operation.path.clone()))
+            } else {
+                ("Pin", crate::Message::PinFile(operation.path.clone()))
+            };
+            list = list.push(file_row(
+                select_state,
+                pin_state,
+                &operation.path,
+                label,
+                pin_label,
+                pin_message,
+                stale.contains(&operation.path),
+            ));
+        }
+
+        let mut content = Column::new()
+            .spacing(10)
+            .width(Length::Units(600))
+            .push(Text::new("Recent Files").size(24))
+            .push(Container::new(list).style(AlternateRowDark).padding(5));
+
+        if let Some(status) = &self.status {
+            content = content.push(
+                Text::new(status)
+                    .size(14)
+                    .color(Color::from_rgb(0.8, 0.2, 0.2)),
+            );
+        }
+
+        content
+            .push(
+                Button::new(&mut self.clear_button, Text::new("Clear History").size(16))
+                    .style(OrangeButton)
+                    .on_press(crate::Message::ClearRecentFiles),
+            )
+            .into()
+    }
+}
+
+// A file that can be selected, with a pin toggle; stale files can only be unpinned
+fn file_row<'a>(
+    select_state: &'a mut button::State,
+    pin_state: &'a mut button::State,
+    path: &Path,
+    label: String,
+    pin_label: &str,
+    pin_message: crate::Message,
+    stale: bool,
+) -> Element<'a, crate::Message> {
+    let mut select = Button::new(select_state, Text::new(label).size(14)).width(Length::Fill);
+    if !stale {
+        select = select.on_press(crate::Message::SelectRecentFile(path.to_path_buf()));
+    }
+
+    let mut details = Column::new()
+        .width(Length::Fill)
+        .push(select)
+        .push(hint(&path.to_string_lossy()));
+    if stale {
+        details = details.push(
+            Text::new("No longer exists")
+                .size(12)
+                .color(Color::from_rgb(0.8, 0.2, 0.2)),
+        );
+    }
+
+    Row::new()
+        .spacing(10)
+        .align_items(Alignment::Center)
+        .push(details)
+        .push(
+            Button::new(pin_state, Text::new(pin_label).size(14))
+                .style(BlueButton)
+                .on_press(pin_message),
+        )
+        .into()
+}
+
+fn hint(text: &str) -> Text {
+    Text::new(text)
+        .size(12)
+        .color(Color::from_rgb(0.5, 0.5, 0.5))
+}
+
+fn file_label(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string_lossy().to_string())
+}
+
+// Rough age of a timestamp for the list
+fn ago(timestamp: u64) -> String {
+    let seconds = now().saturating_sub(timestamp);
+    match seconds {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{} min ago", seconds / 60),
+        3600..=86_399 => format!("{} h ago", seconds / 3600),
+        _ => format!("{} days ago", seconds / 86_400),
+    }
+}
+
+// Seconds since the Unix epoch
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
@@ -0,0 +1,590 @@
+// src/recovery.rs
+
+use crate::kit;
+use crate::login::reset_credentials;
+use crate::session;
+use crate::store::{AlternateRowDark, BlueBorderContainer, BlueButton, GreenButton};
+use crate::vault::Vault;
+use age::secrecy::{ExposeSecret, SecretString};
+use hex::{decode, encode};
+use iced::qr_code::{self, QRCode};
+use iced::{
+    alignment, button, scrollable, slider, text_input, Alignment, Button, Color, Column, Container,
+    Element, Image, Length, Row, Scrollable, Slider, Text, TextInput,
+};
+use sharks::{Share, Sharks};
+use std::convert::TryFrom;
+use std::fs;
+
+// Prefix identifying a printed lockbox recovery share
+const SHARE_PREFIX: &str = "lockbox-share";
+
+// Split the vault identity into `shares` printable shares, any `threshold` of
+// which recover it
+pub fn split(secret: &SecretString, threshold: u8, shares: u8) -> Vec<String> {
+    Sharks(threshold)
+        .dealer(secret.expose_secret().as_bytes())
+        .take(shares as usize)
+        .map(|share| {
+            format!(
+                "{}:{}:{}",
+                SHARE_PREFIX,
+                threshold,
+                encode(Vec::from(&share))
+            )
+        })
+        .collect()
+}
+
+// Combine printed shares back into the vault identity
+pub fn combine(shares: &[String]) -> Result<String, String> {
+    let mut threshold = None;
+    let mut parsed = Vec::new();
+
+    for text in shares
+        .iter()
+        .map(|share| share.trim())
+        .filter(|s| !s.is_empty())
+    {
+        let parts: Vec<&str> = text.split(':').collect();
+        if parts.len() != 3 || parts[0] != SHARE_PREFIX {
+            return Err(format!("Not a lockbox recovery share: {}", text));
+        }
+        let share_threshold: u8 = parts[1]
+            .parse()
+            .map_err(|_| format!("Share has an invalid threshold: {}", text))?;
+        if threshold.is_some_and(|threshold| threshold != share_threshold) {
+            return Err("Shares come from different recovery setups".to_string());
+        }
+        threshold = Some(share_threshold);
+
+        let bytes = decode(parts[2]).map_err(|_| format!("Share is corrupted: {}", text))?;
+        parsed.push(
+            Share::try_from(bytes.as_slice())
+                .map_err(|_| format!("Share is corrupted: {}", text))?,
+        );
+    }
+
+    let threshold = threshold.ok_or("Enter at least one share")?;
+    let secret = Sharks(threshold)
+        .recover(&parsed)
+        .map_err(|_| format!("At least {} different shares are needed", threshold))?;
+    String::from_utf8(secret).map_err(|_| "The shares do not combine to a valid key".to_string())
+}
+
+// A generated share with its QR code
+struct ShareView {
+    text: String,
+    qr_code: Option<qr_code::State>,
+    copy_button: button::State,
+}
+
+// Define the state of the recovery setup page
+pub struct RecoveryPage {
+    threshold: u8,
+    shares: u8,
+    generated: Vec<ShareView>,
+    status: Option<String>,
+    scroll: scrollable::State,
+    threshold_slider: slider::State,
+    shares_slider: slider::State,
+    create_button: button::State,
+    save_button: button::State,
+    kit_button: button::State,
+    back_button: button::State,
+}
+
+impl Default for RecoveryPage {
+    fn default() -> Self {
+        Self {
+            threshold: 3,
+            shares: 5,
+            generated: Vec::new(),
+            status: None,
+            scroll: scrollable::State::new(),
+            threshold_slider: slider::State::new(),
+            shares_slider: slider::State::new(),
+            create_button: button::State::new(),
+            save_button: button::State::new(),
+            kit_button: button::State::new(),
+            back_button: button::State::new(),
+        }
+    }
+}
+
+impl RecoveryPage {
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn threshold_changed(&mut self, value: u8) {
+        self.threshold = value;
+        self.shares = self.shares.max(value);
+        self.generated.clear();
+    }
+
+    pub fn shares_changed(&mut self, value: u8) {
+        self.shares = value;
+        self.threshold = self.threshold.min(value);
+        self.generated.clear();
+    }
+
+    pub fn create_shares(&mut self, vault: &Vault) {
+        self.generated = split(&vault.identity_secret(), self.threshold, self.shares)
+            .into_iter()
+            .map(|text| ShareView {
+                qr_code: qr_code::State::new(&text).ok(),
+                text,
+                copy_button: button::State::new(),
+            })
+            .collect();
+        self.status = Some(format!(
+            "Give each share to a different person or place. Any {} of them recover your vault.",
+            self.threshold
+        ));
+    }
+
+    // Text of a generated share, for copying to the clipboard
+    pub fn share(&self, index: usize) -> Option<String> {
+        self.generated.get(index).map(|share| share.text.clone())
+    }
+
+    // Write every share to its own printable text file
+    pub fn save_shares(&mut self, username: &str) {
+        let folder = match session::output_dialog().pick_folder() {
+            Some(folder) => folder,
+            None => return,
+        };
+        session::remember_output(&folder);
+
+        let total = self.generated.len();
+        for (index, share) in self.generated.iter().enumerate() {
+            let contents = format!(
+                "lockbox recovery share {} of {} for {}\n\n{}\n\n\
+                 Any {} shares reset the password of this account from the login screen.\n",
+                index + 1,
+                total,
+                username,
+                share.text,
+                self.threshold
+            );
+            let path = folder.join(format!("lockbox-share-{}.txt", index + 1));
+            if let Err(e) = fs::write(&path, contents) {
+                self.status = Some(format!("Failed to write {:?}: {:?}", path, e));
+                return;
+            }
+        }
+        self.status = Some(format!("Saved {} shares to {}", total, folder.display()));
+    }
+
+    // Write a printable recovery kit with the vault key as checksummed words
+    pub fn create_kit(&mut self, username: &str, vault: &Vault) {
+        let dest = match session::output_dialog()
+            .add_filter("SVG", &["svg"])
+            .set_file_name("lockbox-recovery-kit.svg")
+            .save_file()
+        {
+            Some(dest) => dest,
+            None => return,
+        };
+        session::remember_output(&dest);
+
+        self.status = Some(match kit::export(username, vault, &dest) {
+            Ok(()) => format!("Saved recovery kit to {}", dest.display()),
+            Err(e) => e,
+        });
+    }
+
+    pub fn view(&mut self) -> Element<'_, crate::Message> {
+        let logo = Container::new(
+            Image::new("images/logo.png")
+                .width(Length::Units(40))
+                .height(Length::Units(40)),
+        )
+        .padding(10)
+        .align_x(alignment::Horizontal::Center);
+
+        let settings = Column::new()
+            .spacing(10)
+            .width(Length::Units(300))
+            .push(Text::new(format!("Shares: {}", self.shares)).size(16))
+            .push(Slider::new(
+                &mut self.shares_slider,
+                2..=10,
+                self.shares,
+                crate::Message::RecoverySharesChanged,
+            ))
+            .push(Text::new(format!("Needed to recover: {}", self.threshold)).size(16))
+            .push(Slider::new(
+                &mut self.threshold_slider,
+                2..=10,
+                self.threshold,
+                crate::Message::RecoveryThresholdChanged,
+            ))
+            .push(
+                Button::new(&mut self.create_button, Text::new("Create Shares").size(20))
+                    .style(GreenButton)
+                    .on_press(crate::Message::CreateRecoveryShares),
+            );
+
+        let kit = Column::new()
+            .spacing(10)
+            .width(Length::Units(300))
+            .push(Text::new("Print your vault key as 24 words and a QR code.").size(16))
+            .push(
+                Button::new(
+                    &mut self.kit_button,
+                    Text::new("Create Recovery Kit").size(20),
+                )
+                .style(GreenButton)
+                .on_press(crate::Message::CreateRecoveryKit),
+            );
+
+        let mut content = Column::new()
+            .spacing(20)
+            .align_items(Alignment::Center)
+            .push(Text::new("Recovery Setup").size(24))
+            .push(
+                Row::new()
+                    .spacing(20)
+                    .push(
+                        Container::new(settings)
+                            .style(BlueBorderContainer)
+                            .padding(10),
+                    )
+                    .push(Container::new(kit).style(BlueBorderContainer).padding(10)),
+            );
+
+        if !self.generated.is_empty() {
+            let mut list = Scrollable::new(&mut self.scroll)
+                .spacing(10)
+                .height(Length::Units(260))
+                .width(Length::Units(600));
+            for (index, share) in self.generated.iter_mut().enumerate() {
+                let mut row = Row::new().spacing(10).align_items(Alignment::Center);
+                if let Some(qr_code) = &share.qr_code {
+                    row = row.push(QRCode::new(qr_code).cell_size(2));
+                }
+                row = row
+                    .push(Text::new(&share.text).size(12).width(Length::Units(380)))
+                    .push(
+                        Button::new(&mut share.copy_button, Text::new("Copy").size(14))
+                            .style(BlueButton)
+                            .on_press(crate::Message::CopyRecoveryShare(index)),
+                    );
+                list = list.push(Container::new(row).style(AlternateRowDark).padding(5));
+            }
+
+            content = content.push(list).push(
+                Button::new(
+                    &mut self.save_button,
+                    Text::new("Save as Text Files").size(16),
+                )
+                .style(BlueButton)
+                .on_press(crate::Message::SaveRecoveryShares),
+            );
+        }
+
+        if let Some(status) = &self.status {
+            content = content.push(
+                Text::new(status)
+                    .size(14)
+                    .color(Color::from_rgb(0.5, 0.5, 0.5)),
+            );
+        }
+
+        content = content.push(
+            Button::new(&mut self.back_button, Text::new("Back").size(16))
+                .style(BlueButton)
+                .on_press(crate::Message::SwitchToStorePage),
+        );
+
+        let container = Container::new(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y();
+
+        Column::new().push(logo).push(container).into()
+    }
+}
+
+// Define the state of the share-based recovery form on the login screen
+#[derive(Default)]
+pub struct RecoverForm {
+    username: String,
+    use_words: bool,
+    shares: Vec<String>,
+    words: String,
+    password: String,
+    confirm: String,
+    status: Option<Result<String, String>>,
+    username_input: text_input::State,
+    share_inputs: Vec<text_input::State>,
+    words_input: text_input::State,
+    method_button: button::State,
+    password_input: text_input::State,
+    confirm_input: text_input::State,
+    add_share_button: button::State,
+    recover_button: button::State,
+    cancel_button: button::State,
+}
+
+impl RecoverForm {
+    pub fn new() -> Self {
+        Self {
+            shares: vec![String::new(); 2],
+            ..Self::default()
+        }
+    }
+
+    pub fn username_changed(&mut self, value: String) {
+        self.username = value;
+    }
+
+    pub fn share_changed(&mut self, index: usize, value: String) {
+        if let Some(share) = self.shares.get_mut(index) {
+            *share = value;
+        }
+    }
+
+    pub fn method_toggled(&mut self) {
+        self.use_words = !self.use_words;
+        self.status = None;
+    }
+
+    pub fn words_changed(&mut self, value: String) {
+        self.words = value;
+    }
+
+    pub fn add_share(&mut self) {
+        self.shares.push(String::new());
+    }
+
+    pub fn password_changed(&mut self, value: String) {
+        self.password = value;
+    }
+
+    pub fn confirm_changed(&mut self, value: String) {
+        self.confirm = value;
+    }
+
+    // Rebuild the vault key from the shares or kit words and set a new password, returning
+    // the username on success
+    pub fn recover(&mut self) -> Option<String> {
+        let result = self.try_recover();
+        self.status = Some(
+            result
+                .as_ref()
+                .map(|_| "Password reset. You can now log in.".to_string())
+                .map_err(|e| e.clone()),
+        );
+        result.ok()
+    }
+
+    fn try_recover(&mut self) -> Result<String, String> {
+        if self.username.is_empty() {
+            return Err("Enter your username".to_string());
+        }
+        if self.password.is_empty() || self.password != self.confirm {
+            return Err("Enter the same new password twice".to_string());
+        }
+
+        let identity = if self.use_words {
+            kit::words_identity(&self.words)?
+        } else {
+            combine(&self.shares)?
+        };
+        Vault::recover(&self.username, &identity, &self.password)?;
+        reset_credentials(&self.username, &self.password)?;
+
+        self.shares = vec![String::new(); self.shares.len()];
+        self.words.clear();
+        self.password.clear();
+        self.confirm.clear();
+        Ok(self.username.clone())
+    }
+
+    pub fn view(&mut self) -> Element<'_, crate::Message> {
+        let mut content = Column::new()
+            .spacing(10)
+            .align_items(Alignment::Center)
+            .push(
+                Text::new(if self.use_words {
+                    "Recover With Kit Words"
+                } else {
+                    "Recover With Shares"
+                })
+                .size(24),
+            )
+            .push(
+                TextInput::new(
+                    &mut self.username_input,
+                    "username",
+                    &self.username,
+                    crate::Message::RecoverUsernameChanged,
+                )
+                .padding(10)
+                .size(20)
+                .width(Length::Units(200)),
+            );
+
+        if self.use_words {
+            content = content.push(
+                TextInput::new(
+                    &mut self.words_input,
+                    "24 recovery words",
+                    &self.words,
+                    crate::Message::RecoverWordsChanged,
+                )
+                .padding(8)
+                .size(14)
+                .width(Length::Units(400)),
+            );
+        } else {
+            // Keep one input state per share field
+            self.share_inputs
+                .resize_with(self.shares.len(), text_input::State::new);
+            for (index, (share, state)) in self
+                .shares
+                .iter()
+                .zip(self.share_inputs.iter_mut())
+                .enumerate()
+            {
+                content = content.push(
+                    TextInput::new(state, "lockbox-share:...", share, move |value| {
+                        crate::Message::RecoverShareChanged(index, value)
+                    })
+                    .padding(8)
+                    .size(14)
+                    .width(Length::Units(400)),
+                );
+            }
+            content = content.push(
+                Button::new(&mut self.add_share_button, Text::new("add share"))
+                    .on_press(crate::Message::AddRecoverShare),
+            );
+        }
+
+        let method_label = if self.use_words {
+            "use shares instead"
+        } else {
+            "use recovery kit words instead"
+        };
+
+        content = content
+            .push(
+                Button::new(&mut self.method_button, Text::new(method_label))
+                    .on_press(crate::Message::ToggleRecoverMethod),
+            )
+            .push(
+                TextInput::new(
+                    &mut self.password_input,
+                    "new password",
+                    &self.password,
+                    crate::Message::RecoverPasswordChanged,
+                )
+                .padding(10)
+                .size(20)
+                .width(Length::Units(200))
+                .password(),
+            )
+            .push(
+                TextInput::new(
+                    &mut self.confirm_input,
+                    "confirm password",
+                    &self.confirm,
+                    crate::Message::RecoverConfirmChanged,
+                )
+                .padding(10)
+                .size(20)
+                .width(Length::Units(200))
+                .password(),
+            )
+            .push(
+                Button::new(&mut self.recover_button, Text::new("recover"))
+                    .on_press(crate::Message::RecoverWithShares),
+            );
+
+        if let Some(status) = &self.status {
+            content = content.push(match status {
+                Ok(message) => Text::new(message)
+                    .size(16)
+                    .color(Color::from_rgb(0.3, 0.6, 0.4)),
+                Err(error) => Text::new(error)
+                    .size(16)
+                    .color(Color::from_rgb(0.8, 0.2, 0.2)),
+            });
+        }
+
+        content
+            .push(
+                Button::new(&mut self.cancel_button, Text::new("back to login"))
+                    .on_press(crate::Message::ToggleRecoverForm),
+            )
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use age::x25519;
+
+    fn secret() -> SecretString {
+        x25519::Identity::generate().to_string()
+    }
+
+    #[test]
+    fn any_threshold_shares_recover_the_identity() {
+        let secret = secret();
+        let shares = split(&secret, 3, 5);
+        assert_eq!(shares.len(), 5);
+        assert!(shares
+            .iter()
+            .all(|share| share.starts_with("lockbox-share:3:")));
+
+        for picked in [
+            &shares[..3],
+            &shares[2..],
+            &[shares[0].clone(), shares[4].clone(), shares[2].clone()][..],
+        ] {
+            assert_eq!(combine(picked).unwrap(), *secret.expose_secret());
+        }
+    }
+
+    #[test]
+    fn too_few_shares_fail() {
+        let shares = split(&secret(), 3, 5);
+        assert!(combine(&shares[..2]).is_err());
+    }
+
+    #[test]
+    fn blank_lines_and_whitespace_are_ignored() {
+        let secret = secret();
+        let shares = split(&secret, 2, 2);
+        let typed = vec![
+            format!("  {}\n", shares[0]),
+            String::new(),
+            shares[1].clone(),
+        ];
+        assert_eq!(combine(&typed).unwrap(), *secret.expose_secret());
+    }
+
+    #[test]
+    fn shares_from_different_setups_are_rejected() {
+        let two = split(&secret(), 2, 3);
+        let three = split(&secret(), 3, 3);
+        assert_eq!(
+            combine(&[two[0].clone(), three[0].clone()]).unwrap_err(),
+            "Shares come from different recovery setups"
+        );
+    }
+
+    #[test]
+    fn malformed_shares_are_rejected() {
+        assert!(combine(&["not a share".to_string()]).is_err());
+        assert!(combine(&["lockbox-share:2:zz".to_string()]).is_err());
+        assert!(combine(&[]).is_err());
+    }
+}
@@ -0,0 +1,204 @@
+// src/session.rs
+
+use crate::config::MIN_WINDOW_SIZE;
+use crate::vault::Vault;
+use dirs::config_dir;
+use iced::{Command, Subscription};
+use iced_native::{window, Event};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+// Vault record holding each user's window and folders
+const SESSION_RECORD: &str = "session";
+
+// Minimized windows on Windows report positions around -32000
+const OFFSCREEN: i32 = -10_000;
+
+// Session shared by every file dialog, loaded from disk on first use
+static CURRENT: RwLock<Option<Session>> = RwLock::new(None);
+
+// Window and folders remembered between runs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    pub window: Option<WindowGeometry>,
+    // Folder files were last opened from
+    pub open_directory: Option<PathBuf>,
+    // Folder files were last saved or written to
+    pub output_directory: Option<PathBuf>,
+}
+
+// Logical window size and outer position, the position only once the window moved
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub width: u32,
+    pub height: u32,
+    pub x: Option<i32>,
+    pub y: Option<i32>,
+}
+
+impl WindowGeometry {
+    // Commands putting the window back where it was
+    pub fn restore<T>(self) -> Command<T> {
+        let mut commands = vec![iced::window::resize(self.width, self.height)];
+        if let (Some(x), Some(y)) = (self.x, self.y) {
+            commands.push(iced::window::move_to(x, y));
+        }
+        Command::batch(commands)
+    }
+}
+
+// Window moves, resizes and close requests
+pub fn events() -> Subscription<crate::Message> {
+    iced_native::subscription::events_with(|event, _status| match event {
+        Event::Window(window::Event::Resized { width, height }) => {
+            Some(crate::Message::WindowResized(width, height))
+        }
+        Event::Window(window::Event::Moved { x, y }) => Some(crate::Message::WindowMoved(x, y)),
+        Event::Window(window::Event::CloseRequested) => Some(crate::Message::CloseRequested),
+        _ => None,
+    })
+}
+
+// The machine-wide file only keeps the window, folders stay in each user's vault
+fn path() -> Result<PathBuf, String> {
+    let config_dir = config_dir().ok_or("Failed to get config directory")?;
+    Ok(config_dir.join("lockbox").join("session.toml"))
+}
+
+fn load_last() -> Session {
+    path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| toml::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+pub fn current() -> Session {
+    if let Some(session) = CURRENT.read().ok().and_then(|session| session.clone()) {
+        return session;
+    }
+    let session = load_last();
+    set_current(session.clone());
+    session
+}
+
+fn set_current(session: Session) {
+    if let Ok(mut current) = CURRENT.write() {
+        *current = Some(session);
+    }
+}
+
+fn update(change: impl FnOnce(&mut Session)) {
+    let mut session = current();
+    change(&mut session);
+    set_current(session);
+}
+
+// Minimizing reports a zero size, which is not worth restoring
+pub fn window_resized(width: u32, height: u32) {
+    if width < MIN_WINDOW_SIZE.0 || height < MIN_WINDOW_SIZE.1 {
+        return;
+    }
+    update(|session| {
+        let window = session.window.get_or_insert(WindowGeometry {
+            width,
+            height,
+            x: None,
+            y: None,
+        });
+        window.width = width;
+        window.height = height;
+    });
+}
+
+pub fn window_moved(x: i32, y: i32) {
+    if x <= OFFSCREEN || y <= OFFSCREEN {
+        return;
+    }
+    update(|session| {
+        if let Some(window) = &mut session.window {
+            window.x = Some(x);
+            window.y = Some(y);
+        }
+    });
+}
+
+// File dialog starting in the folder files were last opened from
+pub fn open_dialog() -> rfd::FileDialog {
+    start_in(current().open_directory)
+}
+
+// File dialog starting in the folder files were last written to
+pub fn output_dialog() -> rfd::FileDialog {
+    start_in(current().output_directory)
+}
+
+fn start_in(directory: Option<PathBuf>) -> rfd::FileDialog {
+    match directory.filter(|directory| directory.is_dir()) {
+        Some(directory) => rfd::FileDialog::new().set_directory(directory),
+        None => rfd::FileDialog::new(),
+    }
+}
+
+// Remember the folder of a picked file, or a picked folder itself
+pub fn remember_open(path: &Path) {
+    let directory = folder_of(path);
+    update(|session| session.open_directory = directory);
+}
+
+pub fn remember_output(path: &Path) {
+    let directory = folder_of(path);
+    update(|session| session.output_directory = directory);
+}
+
+fn folder_of(path: &Path) -> Option<PathBuf> {
+    if path.is_dir() {
+        Some(path.to_path_buf())
+    } else {
+        path.parent().map(Path::to_path_buf)
+    }
+}
+
+// Switch to the session saved for a user who just logged in, returning their window
+pub fn restore(vault: &Vault) -> Option<WindowGeometry> {
+    let mut session: Session = vault.load(SESSION_RECORD).unwrap_or_default();
+    let window = session.window;
+    // Keep the current window when this user has none saved yet
+    if window.is_none() {
+        session.window = current().window;
+    }
+    set_current(session);
+    window
+}
+
+// Save the window for the next launch, and everything for the user when logged in
+pub fn save(vault: Option<&Vault>) -> Result<(), String> {
+    let session = current();
+    if let Some(vault) = vault {
+        vault.save(SESSION_RECORD, &session)?;
+    }
+
+    let last = Session {
+        window: session.window,
+        ..Session::default()
+    };
+    let path = path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create config directory: {:?}", e))?;
+    }
+    let toml = toml::to_string_pretty(&last)
+        .map_err(|e| format!("Failed to serialize session: {:?}", e))?;
+    fs::write(path, toml).map_err(|e| format!("Failed to save session: {:?}", e))
+}
+
+// Drop a user's folders on lock so the next user starts fresh
+pub fn forget_user() {
+    update(|session| {
+        session.open_directory = None;
+        session.output_directory = None;
+    });
+}
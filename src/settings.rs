@@ -0,0 +1,491 @@
+// src/settings.rs
+
+use crate::config::{self, Config, ConfirmConfig, WindowConfig};
+use crate::confirm;
+use crate::generator;
+use crate::session;
+use crate::shred;
+use crate::store::{BlueBorderContainer, BlueButton, GreenButton, OrangeButton};
+use crate::theme::{self, ThemeSetting};
+use iced::{
+    alignment, button, pick_list, scrollable, slider, text_input, Alignment, Button, Checkbox,
+    Color, Column, Container, Element, Image, Length, PickList, Row, Scrollable, Slider, Text,
+    TextInput,
+};
+use std::path::PathBuf;
+
+// Define the state of the settings page, editing a copy of the saved config
+#[derive(Default)]
+pub struct SettingsPage {
+    saved: Config,
+    theme: ThemeSetting,
+    output_directory: String,
+    keep_originals: bool,
+    armor: bool,
+    scrypt_log_n: String,
+    auto_lock_minutes: String,
+    min_password_score: u8,
+    window_width: String,
+    window_height: String,
+    confirm: ConfirmConfig,
+    status: Option<Result<String, String>>,
+    scroll: scrollable::State,
+    theme_list: pick_list::State<ThemeSetting>,
+    output_input: text_input::State,
+    browse_button: button::State,
+    scrypt_input: text_input::State,
+    auto_lock_input: text_input::State,
+    score_slider: slider::State,
+    width_input: text_input::State,
+    height_input: text_input::State,
+    save_button: button::State,
+    reset_button: button::State,
+    delete_button: button::State,
+    back_button: button::State,
+}
+
+impl SettingsPage {
+    // Read the config file and apply its theme at startup
+    pub fn load(&mut self) {
+        let config = match config::load() {
+            Ok(config) => config,
+            Err(e) => {
+                self.status = Some(Err(format!("{}, using the defaults", e)));
+                Config::default()
+            }
+        };
+        theme::apply(config.theme);
+        self.fill(config);
+    }
+
+    // Show the saved values whenever the page opens
+    pub fn open(&mut self) {
+        self.fill(config::current());
+        self.status = None;
+    }
+
+    // Undo a theme previewed but never saved
+    pub fn discard(&mut self) {
+        theme::apply(self.saved.theme);
+    }
+
+    fn fill(&mut self, config: Config) {
+        self.theme = config.theme;
+        self.output_directory = config
+            .output
+            .directory
+            .as_ref()
+            .map(|directory| directory.display().to_string())
+            .unwrap_or_default();
+        self.keep_originals = config.output.keep_originals;
+        self.armor = config.output.armor;
+        self.scrypt_log_n = config.security.scrypt_log_n.to_string();
+        self.auto_lock_minutes = config.security.auto_lock_minutes.to_string();
+        self.min_password_score = config.security.min_password_score;
+        self.window_width = config.window.width.to_string();
+        self.window_height = config.window.height.to_string();
+        self.confirm = config.confirm.clone();
+        self.saved = config;
+    }
+
+    // Preview the theme straight away, it is kept once saved
+    pub fn theme_selected(&mut self, setting: ThemeSetting) {
+        self.theme = setting;
+        theme::apply(setting);
+    }
+
+    pub fn output_directory_changed(&mut self, value: String) {
+        self.output_directory = value;
+    }
+
+    pub fn choose_output_directory(&mut self) {
+        if let Some(folder) = session::output_dialog().pick_folder() {
+            session::remember_output(&folder);
+            self.output_directory = folder.display().to_string();
+        }
+    }
+
+    pub fn keep_originals_toggled(&mut self, keep: bool) {
+        self.keep_originals = keep;
+    }
+
+    pub fn armor_toggled(&mut self, armor: bool) {
+        self.armor = armor;
+    }
+
+    pub fn scrypt_log_n_changed(&mut self, value: String) {
+        self.scrypt_log_n = value;
+    }
+
+    pub fn auto_lock_changed(&mut self, value: String) {
+        self.auto_lock_minutes = value;
+    }
+
+    pub fn min_password_score_changed(&mut self, score: u8) {
+        self.min_password_score = score;
+    }
+
+    pub fn window_width_changed(&mut self, value: String) {
+        self.window_width = value;
+    }
+
+    pub fn window_height_changed(&mut self, value: String) {
+        self.window_height = value;
+    }
+
+    pub fn confirmation_toggled(&mut self, action: confirm::Action, ask: bool) {
+        self.confirm.set(action, ask);
+    }
+
+    pub fn delete_failed(&mut self, error: String) {
+        self.status = Some(Err(error));
+    }
+
+    // Validate and save the form, returning the new window size when it changed
+    pub fn save(&mut self) -> Option<WindowConfig> {
+        let result = self.parse().and_then(|config| {
+            config.save()?;
+            Ok(config)
+        });
+        match result {
+            Ok(config) => {
+                let resized = (config.window != self.saved.window).then(|| config.window.clone());
+                self.fill(config);
+                self.status = Some(Ok("Settings saved".to_string()));
+                resized
+            }
+            Err(e) => {
+                self.status = Some(Err(e));
+                None
+            }
+        }
+    }
+
+    // Save the default config, returning the default window size when it changed
+    pub fn reset(&mut self) -> Option<WindowConfig> {
+        let defaults = Config::default();
+        if let Err(e) = defaults.save() {
+            self.status = Some(Err(e));
+            return None;
+        }
+        let resized = (defaults.window != self.saved.window).then(|| defaults.window.clone());
+        theme::apply(defaults.theme);
+        self.fill(defaults);
+        self.status = Some(Ok("Settings reset to defaults".to_string()));
+        resized
+    }
+
+    fn parse(&self) -> Result<Config, String> {
+        let number = |value: &str, name: &str| {
+            value
+                .trim()
+                .parse::<u32>()
+                .map_err(|_| format!("{} must be a whole number", name))
+        };
+        let scrypt_log_n = self
+            .scrypt_log_n
+            .trim()
+            .parse::<u8>()
+            .map_err(|_| "The scrypt work factor must be a whole number".to_string())?;
+        let directory = self.output_directory.trim();
+
+        let mut config = self.saved.clone();
+        config.theme = self.theme;
+        config.output.directory = (!directory.is_empty()).then(|| PathBuf::from(directory));
+        config.output.keep_originals = self.keep_originals;
+        config.output.armor = self.armor;
+        config.security.scrypt_log_n = scrypt_log_n;
+        config.security.auto_lock_minutes =
+            number(&self.auto_lock_minutes, "The auto-lock timeout")?;
+        config.security.min_password_score = self.min_password_score;
+        config.window.width = number(&self.window_width, "The window width")?;
+        config.window.height = number(&self.window_height, "The window height")?;
+        config.confirm = self.confirm.clone();
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn view(&mut self) -> Element<'_, crate::Message> {
+        let logo = Container::new(
+            Image::new("images/logo.png")
+                .width(Length::Units(40))
+                .height(Length::Units(40)),
+        )
+        .padding(10)
+        .align_x(alignment::Horizontal::Center);
+
+        let hint = |text: &str| {
+            Text::new(text)
+                .size(14)
+                .color(Color::from_rgb(0.5, 0.5, 0.5))
+        };
+
+        let theme_layout = Column::new()
+            .spacing(10)
+            .width(Length::Units(400))
+            .push(Text::new("Theme").size(18))
+            .push(
+                PickList::new(
+                    &mut self.theme_list,
+                    &ThemeSetting::ALL[..],
+                    Some(self.theme),
+                    crate::Message::ThemeSelected,
+                )
+                .padding(8)
+                .text_size(16)
+                .width(Length::Fill),
+            )
+            .push(hint(
+                "Follow system uses the OS dark mode setting where it can be detected.",
+            ));
+
+        let output_layout = Column::new()
+            .spacing(10)
+            .width(Length::Units(400))
+            .push(Text::new("Output").size(18))
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .push(
+                        TextInput::new(
+                            &mut self.output_input,
+                            "next to the original file",
+                            &self.output_directory,
+                            crate::Message::OutputDirectoryChanged,
+                        )
+                        .padding(8)
+                        .size(16)
+                        .width(Length::Fill),
+                    )
+                    .push(
+                        Button::new(&mut self.browse_button, Text::new("Browse").size(16))
+                            .style(BlueButton)
+                            .on_press(crate::Message::ChooseOutputDirectory),
+                    ),
+            )
+            .push(
+                Checkbox::new(
+                    self.keep_originals,
+                    "Keep original files",
+                    crate::Message::ToggleKeepOriginals,
+                )
+                .size(16)
+                .text_size(16),
+            )
+            .push(
+                Text::new(format!(
+                    "Originals that are not kept are shredded. {}",
+                    shred::WARNING
+                ))
+                .size(14)
+                .color(Color::from_rgb(0.86, 0.59, 0.26)),
+            )
+            .push(
+                Checkbox::new(
+                    self.armor,
+                    "ASCII armor age files",
+                    crate::Message::ToggleArmorOutput,
+                )
+                .size(16)
+                .text_size(16),
+            )
+            .push(hint(
+                "Kept originals get a .age file beside them, otherwise results replace them.",
+            ));
+
+        let security_layout = Column::new()
+            .spacing(10)
+            .width(Length::Units(400))
+            .push(Text::new("Security").size(18))
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .push(
+                        Text::new("scrypt work factor (AEAD backends only)")
+                            .size(16)
+                            .width(Length::Fill),
+                    )
+                    .push(
+                        TextInput::new(
+                            &mut self.scrypt_input,
+                            "15",
+                            &self.scrypt_log_n,
+                            crate::Message::ScryptWorkFactorChanged,
+                        )
+                        .padding(8)
+                        .size(16)
+                        .width(Length::Units(80)),
+                    ),
+            )
+            .push(hint(
+                "log2 of the scrypt cost for XChaCha20-Poly1305 and AES-256-GCM files. \
+                 The default age backend times its own work factor and ignores this.",
+            ))
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .push(
+                        Text::new("Auto-lock after (minutes)")
+                            .size(16)
+                            .width(Length::Fill),
+                    )
+                    .push(
+                        TextInput::new(
+                            &mut self.auto_lock_input,
+                            "0",
+                            &self.auto_lock_minutes,
+                            crate::Message::AutoLockChanged,
+                        )
+                        .padding(8)
+                        .size(16)
+                        .width(Length::Units(80)),
+                    ),
+            )
+            .push(hint("0 keeps the vault unlocked until you lock it."))
+            .push(
+                Text::new(format!(
+                    "Minimum password strength: {}",
+                    generator::score_label(self.min_password_score)
+                ))
+                .size(16),
+            )
+            .push(Slider::new(
+                &mut self.score_slider,
+                0..=config::MAX_PASSWORD_SCORE,
+                self.min_password_score,
+                crate::Message::MinPasswordScoreChanged,
+            ))
+            .push(hint("Applies to passwords chosen when signing up."));
+
+        let window_layout = Column::new()
+            .spacing(10)
+            .width(Length::Units(400))
+            .push(Text::new("Window").size(18))
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .push(Text::new("Size").size(16).width(Length::Fill))
+                    .push(
+                        TextInput::new(
+                            &mut self.width_input,
+                            "width",
+                            &self.window_width,
+                            crate::Message::WindowWidthChanged,
+                        )
+                        .padding(8)
+                        .size(16)
+                        .width(Length::Units(80)),
+                    )
+                    .push(Text::new("x").size(16))
+                    .push(
+                        TextInput::new(
+                            &mut self.height_input,
+                            "height",
+                            &self.window_height,
+                            crate::Message::WindowHeightChanged,
+                        )
+                        .padding(8)
+                        .size(16)
+                        .width(Length::Units(80)),
+                    ),
+            );
+
+        let mut confirm_layout = Column::new()
+            .spacing(10)
+            .width(Length::Units(400))
+            .push(Text::new("Confirmations").size(18));
+        for action in confirm::Action::ALL {
+            confirm_layout = confirm_layout.push(
+                Checkbox::new(
+                    self.confirm.asks(action),
+                    action.setting_label(),
+                    move |ask| crate::Message::ToggleConfirmation(action, ask),
+                )
+                .size(16)
+                .text_size(16),
+            );
+        }
+        confirm_layout = confirm_layout.push(hint(
+            "Questions answered with \"Don't ask again\" can be turned back on here.",
+        ));
+
+        let account_layout = Column::new()
+            .spacing(10)
+            .width(Length::Units(400))
+            .push(Text::new("Account").size(18))
+            .push(
+                Button::new(
+                    &mut self.delete_button,
+                    Text::new("Delete Account").size(16),
+                )
+                .style(OrangeButton)
+                .on_press(crate::Message::DeleteAccount),
+            )
+            .push(hint(
+                "Erases your login and everything stored in your vault on this machine.",
+            ));
+
+        let mut content = Column::new()
+            .spacing(20)
+            .padding(10)
+            .width(Length::Fill)
+            .align_items(Alignment::Center)
+            .push(Text::new("Settings").size(24));
+        for section in [
+            theme_layout,
+            output_layout,
+            security_layout,
+            window_layout,
+            confirm_layout,
+            account_layout,
+        ] {
+            content = content.push(
+                Container::new(section)
+                    .style(BlueBorderContainer)
+                    .padding(10),
+            );
+        }
+
+        if let Some(status) = &self.status {
+            let (message, color) = match status {
+                Ok(message) => (message, Color::from_rgb(0.3, 0.6, 0.4)),
+                Err(error) => (error, Color::from_rgb(0.8, 0.2, 0.2)),
+            };
+            content = content.push(Text::new(message).size(14).color(color));
+        }
+
+        content = content.push(
+            Row::new()
+                .spacing(10)
+                .push(
+                    Button::new(&mut self.save_button, Text::new("Save").size(16))
+                        .style(GreenButton)
+                        .on_press(crate::Message::SaveSettings),
+                )
+                .push(
+                    Button::new(
+                        &mut self.reset_button,
+                        Text::new("Reset to defaults").size(16),
+                    )
+                    .style(OrangeButton)
+                    .on_press(crate::Message::ResetSettings),
+                )
+                .push(
+                    Button::new(&mut self.back_button, Text::new("Back").size(16))
+                        .style(BlueButton)
+                        .on_press(crate::Message::CloseSettings),
+                ),
+        );
+
+        let scroll = Scrollable::new(&mut self.scroll)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .push(content);
+
+        Column::new().push(logo).push(scroll).into()
+    }
+}
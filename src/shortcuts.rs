@@ -0,0 +1,104 @@
+// src/shortcuts.rs
+
+use crate::store::{AlternateRowDark, BlueBorderContainer};
+use iced::{Column, Container, Element, Length, Row, Text};
+use iced_native::keyboard::{self, KeyCode};
+use iced_native::{event, Event};
+
+// Actions bound to keys
+#[derive(Debug, Clone, Copy)]
+pub enum Shortcut {
+    FocusNext,
+    FocusPrevious,
+    SelectFile,
+    Encrypt,
+    Decrypt,
+    Lock,
+    ToggleHelp,
+    CloseHelp,
+    PreviousLine,
+    NextLine,
+}
+
+// Key combinations listed in the help overlay, Ctrl reads as Cmd on macOS
+const HELP: [(&str, &str); 9] = [
+    ("Enter", "Log in"),
+    ("Tab / Shift+Tab", "Move between login fields"),
+    ("Ctrl+O", "Select a file"),
+    ("Ctrl+E", "Encrypt the selected files or text"),
+    ("Ctrl+D", "Decrypt the selected files or text"),
+    ("Ctrl+L", "Lock and return to the login screen"),
+    ("Up / Down", "Move between lines of a text snippet or note"),
+    ("F1 / Ctrl+/", "Show or hide this list"),
+    ("Esc", "Close this list"),
+];
+
+// Keyboard events mapped to shortcuts
+pub fn events() -> iced::Subscription<crate::Message> {
+    iced_native::subscription::events_with(|event, status| {
+        let (key_code, modifiers) = match event {
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key_code,
+                modifiers,
+            }) => (key_code, modifiers),
+            _ => return None,
+        };
+
+        let shortcut = match key_code {
+            KeyCode::Tab if modifiers.shift() => Shortcut::FocusPrevious,
+            KeyCode::Tab => Shortcut::FocusNext,
+            KeyCode::F1 => Shortcut::ToggleHelp,
+            KeyCode::Escape => Shortcut::CloseHelp,
+            // Text inputs ignore Up and Down, multi-line editors use them to change line
+            KeyCode::Up if status == event::Status::Ignored => Shortcut::PreviousLine,
+            KeyCode::Down if status == event::Status::Ignored => Shortcut::NextLine,
+            // Leave keys a focused text input handled alone
+            _ if status == event::Status::Captured || !modifiers.command() => return None,
+            KeyCode::O => Shortcut::SelectFile,
+            KeyCode::E => Shortcut::Encrypt,
+            KeyCode::D => Shortcut::Decrypt,
+            KeyCode::L => Shortcut::Lock,
+            KeyCode::Slash => Shortcut::ToggleHelp,
+            _ => return None,
+        };
+        Some(crate::Message::KeyboardShortcut(shortcut))
+    })
+}
+
+// Shift state and typed letters, used to tell when Caps Lock is on
+pub fn typing_events() -> iced::Subscription<crate::Message> {
+    iced_native::subscription::events_with(|event, _status| match event {
+        Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+            Some(crate::Message::ModifiersChanged(modifiers.shift()))
+        }
+        Event::Keyboard(keyboard::Event::CharacterReceived(c)) if c.is_alphabetic() => {
+            Some(crate::Message::CharacterTyped(c))
+        }
+        _ => None,
+    })
+}
+
+// Overlay listing every shortcut
+pub fn help_view<'a>() -> Element<'a, crate::Message> {
+    let mut keys = Column::new().spacing(10);
+    let mut actions = Column::new().spacing(10);
+    for (key, action) in HELP {
+        keys = keys.push(Text::new(key).size(18));
+        actions = actions.push(Text::new(action).size(18));
+    }
+
+    let layout = Column::new()
+        .spacing(10)
+        .push(Text::new("Keyboard Shortcuts").size(24))
+        .push(
+            Container::new(Row::new().spacing(20).push(keys).push(actions))
+                .style(AlternateRowDark)
+                .padding(10)
+                .width(Length::Units(450)),
+        );
+
+    Container::new(layout)
+        .style(BlueBorderContainer)
+        .padding(10)
+        .into()
+}
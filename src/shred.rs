@@ -0,0 +1,95 @@
+// src/shred.rs
+
+use rand::rngs::OsRng;
+use rand::RngCore;
+use std::fs::{self, OpenOptions};
+use std::io::{Seek, SeekFrom, Write};
+use std::path::Path;
+
+// Number of overwrite passes used unless the user picks another
+pub const DEFAULT_PASSES: u8 = 3;
+
+// Size of the random buffer written per chunk
+const CHUNK_SIZE: usize = 64 * 1024;
+
+// Shown before shredding since overwriting in place is not guaranteed
+pub const WARNING: &str = "Shredding is best-effort. SSDs, copy-on-write filesystems \
+    (btrfs, APFS, ZFS), snapshots and backups can keep old copies of the data.";
+
+// Overwrite the file with random data, rename it to a random name and unlink it
+pub fn shred(path: &Path, passes: u8) -> Result<(), String> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open file for shredding: {:?}", e))?;
+    let len = file
+        .metadata()
+        .map_err(|e| format!("Failed to read file size: {:?}", e))?
+        .len();
+
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    for _ in 0..passes.max(1) {
+        file.seek(SeekFrom::Start(0))
+            .map_err(|e| format!("Failed to rewind file: {:?}", e))?;
+        let mut remaining = len;
+        while remaining > 0 {
+            let chunk = remaining.min(CHUNK_SIZE as u64) as usize;
+            OsRng.fill_bytes(&mut buffer[..chunk]);
+            file.write_all(&buffer[..chunk])
+                .map_err(|e| format!("Failed to overwrite file: {:?}", e))?;
+            remaining -= chunk as u64;
+        }
+        // Force each pass to disk so passes are not merged in the page cache
+        file.sync_all()
+            .map_err(|e| format!("Failed to sync file: {:?}", e))?;
+    }
+    drop(file);
+
+    // Hide the original name before unlinking
+    let mut name = [0u8; 12];
+    OsRng.fill_bytes(&mut name);
+    let renamed = path.with_file_name(hex::encode(name));
+    fs::rename(path, &renamed).map_err(|e| format!("Failed to rename file: {:?}", e))?;
+    fs::remove_file(&renamed).map_err(|e| format!("Failed to delete file: {:?}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removes_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secret.txt");
+        fs::write(&path, b"attack at dawn").unwrap();
+
+        shred(&path, DEFAULT_PASSES).unwrap();
+
+        assert!(!path.exists());
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn overwrites_contents_before_unlinking() {
+        // A second link keeps the data reachable after the shredded name is gone
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secret.txt");
+        let link = dir.path().join("link");
+        let original = b"attack at dawn".repeat(10_000);
+        fs::write(&path, &original).unwrap();
+        fs::hard_link(&path, &link).unwrap();
+
+        shred(&path, 1).unwrap();
+
+        let left = fs::read(&link).unwrap();
+        assert_eq!(left.len(), original.len());
+        assert_ne!(left, original);
+        assert!(!left.windows(14).any(|window| window == b"attack at dawn"));
+    }
+
+    #[test]
+    fn missing_file_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(shred(&dir.path().join("missing"), DEFAULT_PASSES).is_err());
+    }
+}
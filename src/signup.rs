@@ -0,0 +1,247 @@
+// src/signup.rs
+
+use crate::config;
+use crate::generator;
+use crate::login::{account_exists, reset_credentials};
+use iced::{
+    button, text_input, Alignment, Button, Color, Column, Element, Length, ProgressBar, Text,
+    TextInput,
+};
+
+// Usernames name the vault folder, so keep them to a portable set of characters
+pub fn validate_username(username: &str) -> Result<(), String> {
+    if username.len() < 3 || username.len() > 32 {
+        return Err("Usernames must be 3 to 32 characters long".to_string());
+    }
+    if !username
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    {
+        return Err("Usernames may only use letters, digits, '_', '-' and '.'".to_string());
+    }
+    if !username.starts_with(|c: char| c.is_ascii_alphanumeric()) {
+        return Err("Usernames must start with a letter or digit".to_string());
+    }
+    Ok(())
+}
+
+// Define the state of the sign-up form on the login screen
+#[derive(Default)]
+pub struct SignupForm {
+    username: String,
+    password: String,
+    confirm: String,
+    revealed: bool,
+    status: Option<String>,
+    username_input: text_input::State,
+    password_input: text_input::State,
+    confirm_input: text_input::State,
+    reveal_button: button::State,
+    signup_button: button::State,
+    cancel_button: button::State,
+}
+
+impl SignupForm {
+    // Start a form, optionally prefilled from the login form with a hint why
+    pub fn new(username: &str, status: Option<String>) -> Self {
+        Self {
+            username: username.to_string(),
+            status,
+            ..Self::default()
+        }
+    }
+
+    pub fn username_changed(&mut self, value: String) {
+        self.username = value;
+    }
+
+    pub fn password_changed(&mut self, value: String) {
+        self.password = value;
+    }
+
+    pub fn confirm_changed(&mut self, value: String) {
+        self.confirm = value;
+    }
+
+    pub fn toggle_reveal(&mut self) {
+        self.revealed = !self.revealed;
+    }
+
+    // Create the account, returning the credentials to log in with on success
+    pub fn submit(&mut self) -> Option<(String, String)> {
+        match self.try_submit() {
+            Ok(()) => Some((
+                std::mem::take(&mut self.username),
+                std::mem::take(&mut self.password),
+            )),
+            Err(e) => {
+                self.status = Some(e);
+                None
+            }
+        }
+    }
+
+    fn try_submit(&mut self) -> Result<(), String> {
+        validate_username(&self.username)?;
+        if self.password != self.confirm {
+            return Err("The passwords do not match".to_string());
+        }
+        let strength = generator::strength(&self.password, &[&self.username]);
+        let min_score = config::current().security.min_password_score;
+        if strength.score < min_score {
+            return Err(format!(
+                "Choose a stronger password (at least {})",
+                generator::score_label(min_score).to_lowercase()
+            ));
+        }
+        if account_exists() {
+            return Err("An account already exists, log in instead".to_string());
+        }
+
+        reset_credentials(&self.username, &self.password)?;
+        self.confirm.clear();
+        Ok(())
+    }
+
+    pub fn view(&mut self) -> Element<'_, crate::Message> {
+        let strength = generator::strength(&self.password, &[&self.username]);
+        let strength_text = match &strength.feedback {
+            Some(feedback) if !self.password.is_empty() => {
+                format!("Strength: {} - {}", strength.label(), feedback)
+            }
+            _ => format!("Strength: {}", strength.label()),
+        };
+
+        let mut password_input = TextInput::new(
+            &mut self.password_input,
+            "password",
+            &self.password,
+            crate::Message::SignupPasswordChanged,
+        )
+        .padding(10)
+        .size(20)
+        .width(Length::Units(200));
+        let mut confirm_input = TextInput::new(
+            &mut self.confirm_input,
+            "confirm password",
+            &self.confirm,
+            crate::Message::SignupConfirmChanged,
+        )
+        .padding(10)
+        .size(20)
+        .width(Length::Units(200))
+        .on_submit(crate::Message::SignupPressed);
+        if !self.revealed {
+            password_input = password_input.password();
+            confirm_input = confirm_input.password();
+        }
+
+        let mut content = Column::new()
+            .spacing(10)
+            .align_items(Alignment::Center)
+            .push(Text::new("Create Account").size(24))
+            .push(
+                TextInput::new(
+                    &mut self.username_input,
+                    "username",
+                    &self.username,
+                    crate::Message::SignupUsernameChanged,
+                )
+                .padding(10)
+                .size(20)
+                .width(Length::Units(200)),
+            )
+            .push(password_input)
+            .push(confirm_input)
+            .push(
+                ProgressBar::new(0.0..=4.0, strength.score as f32)
+                    .width(Length::Units(200))
+                    .height(Length::Units(8)),
+            )
+            .push(Text::new(strength_text).size(14))
+            .push(
+                Button::new(
+                    &mut self.reveal_button,
+                    Text::new(if self.revealed {
+                        "hide password"
+                    } else {
+                        "show password"
+                    }),
+                )
+                .on_press(crate::Message::ToggleRevealSignup),
+            )
+            .push(
+                Button::new(&mut self.signup_button, Text::new("create account"))
+                    .on_press(crate::Message::SignupPressed),
+            );
+
+        if let Some(status) = &self.status {
+            content = content.push(
+                Text::new(status)
+                    .size(16)
+                    .color(Color::from_rgb(0.8, 0.2, 0.2)),
+            );
+        }
+
+        content
+            .push(
+                Button::new(&mut self.cancel_button, Text::new("back to login"))
+                    .on_press(crate::Message::ToggleSignupForm),
+            )
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn portable_usernames_are_accepted() {
+        for username in ["bob", "alice_92", "j.doe-work", "0day", &"a".repeat(32)] {
+            assert_eq!(validate_username(username), Ok(()), "{}", username);
+        }
+    }
+
+    #[test]
+    fn length_is_limited() {
+        for username in ["", "ab", &"a".repeat(33)] {
+            assert_eq!(
+                validate_username(username).unwrap_err(),
+                "Usernames must be 3 to 32 characters long"
+            );
+        }
+    }
+
+    #[test]
+    fn path_and_unusual_characters_are_rejected() {
+        for username in [
+            "../etc",
+            "a/b",
+            "a\\b",
+            "with space",
+            "émile",
+            "bob:1",
+            "nul\0x",
+        ] {
+            assert_eq!(
+                validate_username(username).unwrap_err(),
+                "Usernames may only use letters, digits, '_', '-' and '.'",
+                "{}",
+                username
+            );
+        }
+    }
+
+    #[test]
+    fn usernames_start_with_a_letter_or_digit() {
+        for username in ["...", ".hidden", "-flag", "_bob"] {
+            assert_eq!(
+                validate_username(username).unwrap_err(),
+                "Usernames must start with a letter or digit",
+                "{}",
+                username
+            );
+        }
+    }
+}
@@ -0,0 +1,50 @@
+// src/spinner.rs
+
+use crate::theme;
+use iced::canvas::{self, path, Cursor, Frame, Geometry, LineCap, Path, Stroke};
+use iced::{Canvas, Element, Length, Rectangle};
+
+// Turn of the arc per animation frame, in radians
+const STEP: f32 = 0.3;
+
+// Rotating arc shown while slow work runs in the background
+pub struct Spinner {
+    angle: f32,
+}
+
+impl Spinner {
+    pub fn new(frame: u32) -> Self {
+        Self {
+            angle: frame as f32 * STEP % std::f32::consts::TAU,
+        }
+    }
+
+    pub fn view<'a>(self, size: u16) -> Element<'a, crate::Message> {
+        Canvas::new(self)
+            .width(Length::Units(size))
+            .height(Length::Units(size))
+            .into()
+    }
+}
+
+impl canvas::Program<crate::Message> for Spinner {
+    fn draw(&self, bounds: Rectangle, _cursor: Cursor) -> Vec<Geometry> {
+        let mut frame = Frame::new(bounds.size());
+        let arc = Path::new(|builder| {
+            builder.arc(path::Arc {
+                center: frame.center(),
+                radius: frame.width().min(frame.height()) / 2.0 - 2.0,
+                start_angle: self.angle,
+                end_angle: self.angle + 4.5,
+            })
+        });
+        frame.stroke(
+            &arc,
+            Stroke::default()
+                .with_color(theme::palette().primary)
+                .with_width(3.0)
+                .with_line_cap(LineCap::Round),
+        );
+        vec![frame.into_geometry()]
+    }
+}
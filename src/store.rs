@@ -1,21 +1,93 @@
-use age::{secrecy::SecretString, Decryptor, Encryptor};
+use crate::cipher::{detect_passphrase, AgePassphrase, AgeRecipients, Backend, Cipher};
+use crate::config;
+use crate::confirm::{self, Allowed, Confirmation, Pending};
+use crate::dragdrop::{self, DropTarget};
+use crate::editor::Editor;
+use crate::history::{self, Kind, Operation};
+use crate::preview::{self, Preview};
+use crate::recent::{Action, RecentPanel};
+use crate::session;
+use crate::shortcuts;
+use crate::shred;
+use crate::theme;
+use crate::vault::{self, Vault};
+use crate::watch::WatchPanel;
+use crate::workcopy::WorkingCopy;
+use age::armor::Format;
+use age::x25519;
 use iced::{
-    alignment, button, container, scrollable, Alignment, Background, Button, Color, Column,
-    Container, Element, Image, Length, Row, Text,
+    alignment, button, container, pick_list, scrollable, slider, Alignment, Background, Button,
+    Color, Column, Container, Element, Image, Length, PickList, Row, Scrollable, Slider, Text,
 };
 use std::fs;
-use std::io::{Read, Write};
-use std::path::PathBuf;
-use std::time::Instant;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 #[derive(Default)]
 pub struct StorePage {
-    scroll: scrollable::State,
     file_select_button: button::State,
     encrypt_button: button::State,
     decrypt_button: button::State,
+    preview_button: button::State,
+    edit_button: button::State,
+    done_button: button::State,
     selected_file: Option<PathBuf>,
+    selection: Vec<PathBuf>,
+    drop_target: DropTarget,
+    batch: Batch,
     file_details: Option<FileDetails>,
+    text_mode: bool,
+    mode_button: button::State,
+    notes_button: button::State,
+    passwords_button: button::State,
+    backup_button: button::State,
+    recovery_button: button::State,
+    watch_button: button::State,
+    env_button: button::State,
+    settings_button: button::State,
+    watch_open: bool,
+    watch_panel: WatchPanel,
+    recent_button: button::State,
+    recent_open: bool,
+    recent: RecentPanel,
+    pin_button: button::State,
+    history_button: button::State,
+    // Finished operations not yet handed to the history page
+    operations: Vec<Operation>,
+    paste_button: button::State,
+    output_scroll: scrollable::State,
+    encrypt_text_button: button::State,
+    decrypt_text_button: button::State,
+    reveal_button: button::State,
+    copy_button: button::State,
+    snippet: Editor,
+    text_output: Option<TextOutput>,
+    text_revealed: bool,
+    text_error: Option<String>,
+    shred_button: button::State,
+    shred_slider: slider::State,
+    shred_passes: Option<u8>,
+    file_status: Option<String>,
+    preview: Option<Preview>,
+    working_copy: Option<WorkingCopy>,
+    backend: Backend,
+    backend_list: pick_list::State<Backend>,
+    show_help: bool,
+}
+
+// Result of the last text snippet operation, kept in memory only
+enum TextOutput {
+    Ciphertext(String),
+    Plaintext(String),
+}
+
+// Outcome of encrypting or decrypting several files at once
+#[derive(Default)]
+struct Batch {
+    done: usize,
+    failed: usize,
+    last_error: Option<String>,
 }
 
 #[derive(Clone)]
@@ -24,22 +96,12 @@ struct FileDetails {
     size: String,
     file_type: String,
     path: String,
+    cipher: String,
     encryption_time: Option<std::time::Duration>,
     decryption_time: Option<std::time::Duration>,
 }
 
 impl StorePage {
-    pub fn new() -> Self {
-        Self {
-            scroll: scrollable::State::new(),
-            file_select_button: button::State::new(),
-            encrypt_button: button::State::new(),
-            decrypt_button: button::State::new(),
-            selected_file: None,
-            file_details: None,
-        }
-    }
-
     // Method to extract file details
     fn get_file_details(&self) -> Option<FileDetails> {
         self.selected_file.as_ref().and_then(|path| {
@@ -47,13 +109,7 @@ impl StorePage {
             match fs::metadata(path) {
                 Ok(metadata) => {
                     // Convert file size to human-readable format
-                    let size = if metadata.len() < 1024 {
-                        format!("{} bytes", metadata.len())
-                    } else if metadata.len() < 1024 * 1024 {
-                        format!("{:.2} KB", metadata.len() as f64 / 1024.0)
-                    } else {
-                        format!("{:.2} MB", metadata.len() as f64 / (1024.0 * 1024.0))
-                    };
+                    let size = history::format_size(metadata.len());
 
                     // Determine file type
                     let file_type = path
@@ -66,6 +122,9 @@ impl StorePage {
                         size,
                         file_type: file_type.to_string(), // Update this as needed
                         path: path.to_string_lossy().to_string(),
+                        cipher: detect_file_backend(path)
+                            .map(|backend| backend.to_string())
+                            .unwrap_or_else(|| "Not encrypted".to_string()),
                         encryption_time: None,
                         decryption_time: None,
                     })
@@ -75,7 +134,7 @@ impl StorePage {
         })
     }
 
-    pub fn view(&mut self) -> Element<crate::Message> {
+    pub fn view(&mut self) -> Element<'_, crate::Message> {
         let logo = Container::new(
             Image::new("images/logo.png")
                 .width(Length::Units(40))
@@ -84,6 +143,66 @@ impl StorePage {
         .padding(10)
         .align_x(alignment::Horizontal::Center);
 
+        let mode_button = Button::new(
+            &mut self.mode_button,
+            Text::new(if self.text_mode { "Files" } else { "Text" }).size(16),
+        )
+        .style(BlueButton)
+        .on_press(crate::Message::ToggleTextMode);
+
+        if self.text_mode {
+            let content = Self::text_view(
+                &mut self.snippet,
+                &mut self.paste_button,
+                &mut self.output_scroll,
+                &mut self.encrypt_text_button,
+                &mut self.decrypt_text_button,
+                &mut self.reveal_button,
+                &mut self.copy_button,
+                &self.text_output,
+                self.text_revealed,
+                &self.text_error,
+            )
+            .push(mode_button);
+
+            let container = Container::new(content)
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .center_x()
+                .center_y();
+
+            return Column::new().push(logo).push(container).into();
+        }
+
+        // The drop area covers the page while files are dragged over the window
+        if self.drop_target.is_hovering() {
+            return Column::new()
+                .push(logo)
+                .push(self.drop_target.view())
+                .into();
+        }
+
+        if self.show_help {
+            let container = Container::new(shortcuts::help_view())
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .center_x()
+                .center_y();
+
+            return Column::new().push(logo).push(container).into();
+        }
+
+        // Decrypted contents replace the page until the preview is closed
+        if let Some(preview) = &mut self.preview {
+            let container = Container::new(preview.view())
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .center_x()
+                .center_y();
+
+            return Column::new().push(logo).push(container).into();
+        }
+
         let file_select_button = Button::new(
             &mut self.file_select_button,
             Text::new("Select File").size(20),
@@ -99,36 +218,239 @@ impl StorePage {
             .style(OrangeButton)
             .on_press(crate::Message::DecryptFile);
 
+        let preview_button = Button::new(&mut self.preview_button, Text::new("Preview").size(20))
+            .style(BlueButton)
+            .on_press(crate::Message::PreviewFile);
+
+        let edit_button = Button::new(
+            &mut self.edit_button,
+            Text::new("Open for Editing").size(20),
+        )
+        .style(BlueButton)
+        .on_press(crate::Message::OpenForEditing);
+
+        let backend_list = PickList::new(
+            &mut self.backend_list,
+            &Backend::ALL[..],
+            Some(self.backend),
+            crate::Message::CipherSelected,
+        )
+        .padding(8)
+        .text_size(16);
+
         let button_row = Row::new()
             .spacing(20)
+            .align_items(Alignment::Center)
             .push(file_select_button)
+            .push(backend_list)
             .push(encrypt_button)
-            .push(decrypt_button);
+            .push(decrypt_button)
+            .push(preview_button)
+            .push(edit_button);
+
+        let notes_button = Button::new(&mut self.notes_button, Text::new("Notes").size(16))
+            .style(BlueButton)
+            .on_press(crate::Message::SwitchToNotesPage);
+
+        let passwords_button =
+            Button::new(&mut self.passwords_button, Text::new("Passwords").size(16))
+                .style(BlueButton)
+                .on_press(crate::Message::SwitchToPasswordsPage);
+
+        let backup_button = Button::new(&mut self.backup_button, Text::new("Backup").size(16))
+            .style(BlueButton)
+            .on_press(crate::Message::SwitchToBackupPage);
+
+        let recovery_button =
+            Button::new(&mut self.recovery_button, Text::new("Recovery").size(16))
+                .style(BlueButton)
+                .on_press(crate::Message::SwitchToRecoveryPage);
+
+        let env_button = Button::new(&mut self.env_button, Text::new("Env").size(16))
+            .style(BlueButton)
+            .on_press(crate::Message::SwitchToEnvPage);
+
+        let settings_button =
+            Button::new(&mut self.settings_button, Text::new("Settings").size(16))
+                .style(BlueButton)
+                .on_press(crate::Message::SwitchToSettingsPage);
+
+        let history_button = Button::new(&mut self.history_button, Text::new("History").size(16))
+            .style(BlueButton)
+            .on_press(crate::Message::SwitchToHistoryPage);
+
+        let watch_button = Button::new(
+            &mut self.watch_button,
+            Text::new(if self.watch_open { "Files" } else { "Watch" }).size(16),
+        )
+        .style(BlueButton)
+        .on_press(crate::Message::ToggleWatchPanel);
+
+        let recent_button = Button::new(
+            &mut self.recent_button,
+            Text::new(if self.recent_open { "Files" } else { "Recent" }).size(16),
+        )
+        .style(BlueButton)
+        .on_press(crate::Message::ToggleRecentPanel);
+
+        if self.recent_open {
+            let content = Column::new()
+                .spacing(20)
+                .align_items(Alignment::Center)
+                .push(self.recent.view())
+                .push(recent_button);
+
+            let container = Container::new(content)
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .center_x()
+                .center_y();
+
+            return Column::new().push(logo).push(container).into();
+        }
+
+        if self.watch_open {
+            let content = Column::new()
+                .spacing(20)
+                .align_items(Alignment::Center)
+                .push(self.watch_panel.view())
+                .push(watch_button);
+
+            let container = Container::new(content)
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .center_x()
+                .center_y();
+
+            return Column::new().push(logo).push(container).into();
+        }
 
         let mut content = Column::new()
             .spacing(20)
             .align_items(Alignment::Center)
             .push(Text::new("Select a file to encrypt or decrypt!").size(24))
+            .push(
+                Text::new("Or drag files and folders onto the window, press F1 for shortcuts")
+                    .size(14)
+                    .color(Color::from_rgb(0.5, 0.5, 0.5)),
+            )
             .push(button_row);
 
+        if let Some(choice) = self.drop_target.choice_view(&self.selection) {
+            content = content.push(choice);
+        }
+
+        content = content.push(
+            Row::new()
+                .spacing(10)
+                .push(mode_button)
+                .push(recent_button)
+                .push(notes_button)
+                .push(passwords_button)
+                .push(backup_button)
+                .push(recovery_button)
+                .push(watch_button)
+                .push(env_button)
+                .push(history_button)
+                .push(settings_button),
+        );
+
+        if let Some(copy) = &self.working_copy {
+            let editing = Column::new()
+                .spacing(10)
+                .align_items(Alignment::Center)
+                .push(Text::new(format!("Editing {}", copy.file_name())).size(20))
+                .push(
+                    Text::new("Saved changes are encrypted back automatically.")
+                        .size(14)
+                        .color(Color::from_rgb(0.5, 0.5, 0.5)),
+                )
+                .push(
+                    Button::new(&mut self.done_button, Text::new("Done").size(16))
+                        .style(GreenButton)
+                        .on_press(crate::Message::FinishEditing),
+                );
+            content = content.push(
+                Container::new(editing)
+                    .style(BlueBorderContainer)
+                    .padding(10),
+            );
+        }
+
+        if let Some(status) = &self.file_status {
+            content = content.push(
+                Text::new(status)
+                    .size(14)
+                    .color(Color::from_rgb(0.5, 0.5, 0.5)),
+            );
+        }
+
         if let Some(details) = &self.file_details {
+            let passes = self.shred_passes.unwrap_or(shred::DEFAULT_PASSES);
+            let shred_layout = Column::new()
+                .spacing(10)
+                .width(Length::Units(300))
+                .push(Text::new("Shred File").size(24))
+                .push(Text::new(format!("Overwrite passes: {}", passes)).size(18))
+                .push(Slider::new(
+                    &mut self.shred_slider,
+                    1..=10,
+                    passes,
+                    crate::Message::ShredPassesChanged,
+                ))
+                .push(
+                    Text::new(shred::WARNING)
+                        .size(14)
+                        .color(Color::from_rgb(0.86, 0.59, 0.26)),
+                )
+                .push(
+                    Button::new(&mut self.shred_button, Text::new("Shred").size(20))
+                        .style(OrangeButton)
+                        .on_press(crate::Message::ShredFile),
+                );
+
             let labels_column = Column::new()
                 .spacing(10)
                 .push(Text::new("Filename:").size(18))
                 .push(Text::new("Size:").size(18))
                 .push(Text::new("Type:").size(18))
-                .push(Text::new("Path:").size(18));
+                .push(Text::new("Path:").size(18))
+                .push(Text::new("Cipher:").size(18));
 
             let values_column = Column::new()
                 .spacing(10)
                 .push(Text::new(&details.filename).size(18))
                 .push(Text::new(&details.size).size(18))
                 .push(Text::new(&details.file_type).size(18))
-                .push(Text::new(&details.path).size(18));
+                .push(Text::new(&details.path).size(18))
+                .push(Text::new(&details.cipher).size(18));
+
+            let pinned = self
+                .selected_file
+                .as_ref()
+                .is_some_and(|path| self.recent.is_favorite(path));
+            let mut pin_button = Button::new(
+                &mut self.pin_button,
+                Text::new(if pinned { "Unpin" } else { "Pin to Favorites" }).size(16),
+            )
+            .style(BlueButton);
+            if let Some(path) = &self.selected_file {
+                pin_button = pin_button.on_press(if pinned {
+                    crate::Message::UnpinFile(path.clone())
+                } else {
+                    crate::Message::PinFile(path.clone())
+                });
+            }
 
             let details_layout = Column::new()
                 .spacing(10)
-                .push(Text::new("File Details").size(24))
+                .push(
+                    Row::new()
+                        .spacing(20)
+                        .align_items(Alignment::Center)
+                        .push(Text::new("File Details").size(24))
+                        .push(pin_button),
+                )
                 .push(
                     Container::new(
                         Row::new()
@@ -148,14 +470,14 @@ impl StorePage {
             let performance_values_column = Column::new()
                 .spacing(10)
                 .push(
-                    Text::new(&format!(
+                    Text::new(format!(
                         "{:.5} s",
                         details.encryption_time.unwrap_or_default().as_secs_f64()
                     ))
                     .size(18),
                 )
                 .push(
-                    Text::new(&format!(
+                    Text::new(format!(
                         "{:.5} s",
                         details.decryption_time.unwrap_or_default().as_secs_f64()
                     ))
@@ -184,7 +506,17 @@ impl StorePage {
                 .style(BlueBorderContainer)
                 .padding(10);
 
-            content = content.push(bordered_details).push(bordered_performance);
+            let bordered_shred = Container::new(shred_layout)
+                .style(BlueBorderContainer)
+                .padding(10);
+
+            content = content.push(
+                Row::new()
+                    .spacing(20)
+                    .push(bordered_details)
+                    .push(bordered_shred),
+            );
+            content = content.push(bordered_performance);
         }
 
         let container = Container::new(content)
@@ -196,105 +528,868 @@ impl StorePage {
         Column::new().push(logo).push(container).into()
     }
 
+    // Builds the text snippet view
+    #[allow(clippy::too_many_arguments)]
+    fn text_view<'a>(
+        snippet: &'a mut Editor,
+        paste_button: &'a mut button::State,
+        output_scroll: &'a mut scrollable::State,
+        encrypt_text_button: &'a mut button::State,
+        decrypt_text_button: &'a mut button::State,
+        reveal_button: &'a mut button::State,
+        copy_button: &'a mut button::State,
+        text_output: &Option<TextOutput>,
+        text_revealed: bool,
+        text_error: &Option<String>,
+    ) -> Column<'a, crate::Message> {
+        let snippet_input = Container::new(snippet.view(
+            "Type or paste plaintext or armored ciphertext",
+            150,
+            crate::Message::SnippetEdited,
+            crate::Message::SnippetNewLine,
+        ))
+        .width(Length::Units(500));
+
+        // Pasting through the clipboard keeps the line breaks armored text needs
+        let paste_button = Button::new(paste_button, Text::new("Paste").size(20))
+            .style(BlueButton)
+            .on_press(crate::Message::PasteSnippet);
+
+        let encrypt_text_button = Button::new(encrypt_text_button, Text::new("Encrypt").size(20))
+            .style(GreenButton)
+            .on_press(crate::Message::EncryptText);
+
+        let decrypt_text_button = Button::new(decrypt_text_button, Text::new("Decrypt").size(20))
+            .style(OrangeButton)
+            .on_press(crate::Message::DecryptText);
+
+        let mut content = Column::new()
+            .spacing(20)
+            .align_items(Alignment::Center)
+            .push(Text::new("Encrypt or decrypt a text snippet!").size(24))
+            .push(snippet_input)
+            .push(
+                Row::new()
+                    .spacing(20)
+                    .push(encrypt_text_button)
+                    .push(decrypt_text_button)
+                    .push(paste_button),
+            );
+
+        if let Some(error) = text_error {
+            content = content.push(
+                Text::new(error)
+                    .size(16)
+                    .color(Color::from_rgb(0.8, 0.2, 0.2)),
+            );
+        }
+
+        if let Some(output) = text_output {
+            let (title, value, reveal_button) = match output {
+                TextOutput::Ciphertext(armored) => ("Ciphertext", armored.clone(), None),
+                TextOutput::Plaintext(plaintext) => {
+                    // Keep the plaintext masked until the user asks to see it
+                    let value = if text_revealed {
+                        plaintext.clone()
+                    } else {
+                        "•".repeat(plaintext.chars().count())
+                    };
+                    let reveal_button = Button::new(
+                        reveal_button,
+                        Text::new(if text_revealed { "Hide" } else { "Show" }).size(16),
+                    )
+                    .style(BlueButton)
+                    .on_press(crate::Message::ToggleRevealText);
+                    ("Plaintext", value, Some(reveal_button))
+                }
+            };
+
+            let copy_button = Button::new(copy_button, Text::new("Copy").size(16))
+                .style(BlueButton)
+                .on_press(crate::Message::CopyTextOutput);
+
+            let mut actions = Row::new().spacing(10);
+            if let Some(reveal_button) = reveal_button {
+                actions = actions.push(reveal_button);
+            }
+            actions = actions.push(copy_button);
+
+            let output_layout = Column::new()
+                .spacing(10)
+                .push(Text::new(title).size(24))
+                .push(
+                    Container::new(
+                        Scrollable::new(output_scroll)
+                            .height(Length::Units(150))
+                            .push(Text::new(value).size(14)),
+                    )
+                    .style(AlternateRowDark)
+                    .padding(10)
+                    .width(Length::Units(500)),
+                )
+                .push(actions);
+
+            content = content.push(
+                Container::new(output_layout)
+                    .style(BlueBorderContainer)
+                    .padding(10),
+            );
+        }
+
+        content
+    }
+
+    pub fn toggle_text_mode(&mut self) {
+        self.text_mode = !self.text_mode;
+        // Never keep a snippet around once the user leaves text mode
+        self.snippet = Editor::default();
+        self.text_output = None;
+        self.text_revealed = false;
+        self.text_error = None;
+    }
+
+    pub fn snippet_edited(&mut self, index: usize, value: String) {
+        self.snippet.edit(index, value);
+    }
+
+    pub fn snippet_new_line(&mut self, index: usize) {
+        self.snippet.split(index);
+    }
+
+    pub fn snippet_pasted(&mut self, text: &str) {
+        self.snippet.paste(text);
+    }
+
+    pub fn move_snippet_line(&mut self, down: bool) {
+        self.snippet.move_focus(down);
+    }
+
+    pub fn toggle_reveal_text(&mut self) {
+        self.text_revealed = !self.text_revealed;
+    }
+
+    // Returns the current text output and whether it is decrypted plaintext
+    pub fn text_output(&self) -> Option<(String, bool)> {
+        self.text_output.as_ref().map(|output| match output {
+            TextOutput::Ciphertext(text) => (text.clone(), false),
+            TextOutput::Plaintext(text) => (text.clone(), true),
+        })
+    }
+
+    pub fn encrypt_text(&mut self) {
+        if self.snippet.is_empty() {
+            return;
+        }
+
+        let start_time = Instant::now();
+        let snippet = self.snippet.text();
+        let result = encrypt_bytes(snippet.as_bytes(), Format::AsciiArmor);
+        self.operations.push(Operation::new(
+            Kind::EncryptText,
+            None,
+            Some(snippet.len() as u64),
+            start_time.elapsed(),
+            &result,
+        ));
+        match result {
+            Ok(armored) => {
+                self.text_output = Some(TextOutput::Ciphertext(
+                    String::from_utf8_lossy(&armored).to_string(),
+                ));
+                self.text_error = None;
+            }
+            Err(e) => self.text_error = Some(e),
+        }
+        self.text_revealed = false;
+    }
+
+    pub fn decrypt_text(&mut self) {
+        if self.snippet.is_empty() {
+            return;
+        }
+
+        // Pasting can add surrounding whitespace the armor parser rejects
+        let armored = format!("{}\n", self.snippet.text().trim());
+        let start_time = Instant::now();
+        let result = decrypt_bytes(armored.as_bytes()).and_then(|plaintext| {
+            String::from_utf8(plaintext).map_err(|_| "Decrypted data is not valid text".to_string())
+        });
+        self.operations.push(Operation::new(
+            Kind::DecryptText,
+            None,
+            Some(armored.len() as u64),
+            start_time.elapsed(),
+            &result,
+        ));
+        match result {
+            Ok(plaintext) => {
+                self.text_output = Some(TextOutput::Plaintext(plaintext));
+                self.text_error = None;
+            }
+            Err(e) => self.text_error = Some(e),
+        }
+        self.text_revealed = false;
+    }
+
     pub fn trigger_file_selection(&mut self) {
-        if let Some(path) = rfd::FileDialog::new().pick_file() {
-            self.selected_file = Some(path);
-            // Update file details when a new file is selected
-            self.file_details = self.get_file_details();
+        if let Some(path) = session::open_dialog().pick_file() {
+            session::remember_open(&path);
+            self.select(path);
         }
     }
 
-    pub fn encrypt_file(&mut self) {
-        if let Some(path) = &self.selected_file {
-            let start_time = Instant::now();
+    fn select(&mut self, path: PathBuf) {
+        self.selection = vec![path.clone()];
+        self.selected_file = Some(path);
+        self.file_status = None;
+        // Update file details when a new file is selected
+        self.file_details = self.get_file_details();
+    }
+
+    // Check for moved or deleted files each time the panel opens
+    pub fn toggle_recent_panel(&mut self) {
+        self.recent_open = !self.recent_open;
+        if self.recent_open {
+            self.watch_open = false;
+            self.recent.refresh();
+        }
+    }
+
+    // Select a recent or favorite file and go back to its details
+    pub fn select_recent_file(&mut self, path: PathBuf) {
+        self.recent.refresh();
+        if self.recent.is_stale(&path) {
+            return;
+        }
+        self.recent_open = false;
+        self.select(path);
+    }
+
+    pub fn pin_file(&mut self, path: PathBuf) {
+        self.recent.pin(path);
+    }
+
+    pub fn unpin_file(&mut self, path: &Path) {
+        self.recent.unpin(path);
+    }
+
+    pub fn clear_recent(&mut self) {
+        self.recent.clear();
+    }
+
+    pub fn load_recent(&mut self, vault: &Vault) {
+        self.recent.load(vault);
+    }
 
-            let file_content = fs::read(path).expect("Failed to read file");
-            let encryptor =
-                Encryptor::with_user_passphrase(SecretString::new("password".to_string()));
-            let mut encrypted_output = Vec::new();
-            let mut writer = encryptor
-                .wrap_output(&mut encrypted_output)
-                .expect("Failed to create encryptor");
-            writer
-                .write_all(&file_content)
-                .expect("Failed to encrypt file");
-            writer.finish().expect("Failed to finalize encryption");
-            fs::write(path, encrypted_output).expect("Failed to write encrypted file");
-
-            let duration = start_time.elapsed();
-
-            // Update file details with encryption time
-            if let Some(details) = &mut self.file_details {
-                details.encryption_time = Some(duration);
+    pub fn save_recent(&mut self, vault: &Vault) {
+        self.recent.save(vault);
+    }
+
+    pub fn file_hovered(&mut self) {
+        self.drop_target.file_hovered();
+    }
+
+    pub fn files_hovered_left(&mut self) {
+        self.drop_target.hover_left();
+    }
+
+    // Dropped files arrive one event each, so they are held until the drop is complete
+    pub fn file_dropped(&mut self, path: PathBuf) {
+        self.drop_target.dropped(path);
+    }
+
+    pub fn is_drop_waiting(&self) -> bool {
+        self.drop_target.is_waiting()
+    }
+
+    // Select a whole drop at once and offer to encrypt or decrypt it
+    pub fn process_drop(&mut self) {
+        let paths = self.drop_target.take();
+        self.batch = Batch::default();
+
+        let mut files = Vec::new();
+        for path in &paths {
+            dragdrop::collect_files(path, &mut files);
+        }
+        self.selection = files;
+        self.selected_file = self.selection.first().cloned();
+        self.file_details = self.get_file_details();
+        self.file_status = match self.selection.len() {
+            0 => Some("No files found in the dropped folder".to_string()),
+            1 => None,
+            count => Some(format!("{} files selected", count)),
+        };
+        self.drop_target.offer(self.selection.clone());
+    }
+
+    pub fn dismiss_drop(&mut self) {
+        self.drop_target.dismiss();
+    }
+
+    // Decrypt the selected file into memory without writing plaintext to disk
+    pub fn preview_file(&mut self) {
+        let path = match &self.selected_file {
+            Some(path) => path,
+            None => return,
+        };
+
+        // Previews live in memory, so large files must be decrypted to disk instead
+        if file_size(path).is_some_and(|size| size > preview::MAX_SIZE) {
+            self.file_status = Some(format!(
+                "File is too large to preview (over {} MB), decrypt it instead",
+                preview::MAX_SIZE / (1024 * 1024)
+            ));
+            return;
+        }
+
+        let result = fs::read(path)
+            .map_err(|e| format!("Failed to read file: {:?}", e))
+            .and_then(|content| decrypt_bytes(&content));
+        match result {
+            Ok(bytes) => {
+                let filename = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                self.preview = Some(Preview::new(filename, bytes));
+                self.file_status = None;
             }
+            Err(e) => self.file_status = Some(e),
         }
     }
 
-    pub fn decrypt_file(&mut self) {
+    // Dropping the preview wipes its buffers
+    pub fn close_preview(&mut self) {
+        self.preview = None;
+    }
+
+    pub fn is_text_mode(&self) -> bool {
+        self.text_mode
+    }
+
+    // Whether the file view is on screen rather than a panel or overlay replacing it
+    pub fn shows_files(&self) -> bool {
+        !self.text_mode
+            && !self.drop_target.is_hovering()
+            && !self.show_help
+            && self.preview.is_none()
+            && !self.recent_open
+            && !self.watch_open
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.working_copy.is_some()
+    }
+
+    // Decrypt the selected file to a private temp copy and open it in its default app
+    pub fn open_for_editing(&mut self) {
+        if self.working_copy.is_some() {
+            return;
+        }
         if let Some(path) = &self.selected_file {
-            let start_time = Instant::now();
+            match WorkingCopy::open(path) {
+                Ok(copy) => {
+                    self.working_copy = Some(copy);
+                    self.file_status = None;
+                }
+                Err(e) => self.file_status = Some(e),
+            }
+        }
+    }
 
-            let file_content = match fs::read(path) {
-                Ok(content) => content,
-                Err(e) => {
-                    eprintln!("Failed to read file: {:?}", e);
-                    return;
+    // Re-encrypt the working copy whenever the editor saves it
+    pub fn check_working_copy(&mut self) {
+        match self.sync_working_copy() {
+            Some(Ok(true)) => {
+                if let Some(copy) = &self.working_copy {
+                    self.file_status = Some(format!("Saved {}", copy.file_name()));
                 }
-            };
+            }
+            Some(Err(e)) => self.file_status = Some(e),
+            _ => {}
+        }
+    }
+
+    // Re-encrypt editor saves, adding each save or failure to the history
+    fn sync_working_copy(&mut self) -> Option<Result<bool, String>> {
+        let copy = self.working_copy.as_mut()?;
+        let start_time = Instant::now();
+        let result = copy.sync();
+        if !matches!(result, Ok(false)) {
+            self.operations.push(Operation::new(
+                Kind::SaveWorkingCopy,
+                Some(copy.source()),
+                file_size(copy.source()),
+                start_time.elapsed(),
+                &result,
+            ));
+        }
+        Some(result)
+    }
+
+    // Save any last changes, then wipe the temp copy. Save failures are recorded
+    // as operations; an error is returned when the plaintext could not be wiped.
+    pub fn finish_editing(&mut self) -> Result<(), String> {
+        if self.working_copy.is_some() {
+            // Keep the copy around if its changes could not be saved
+            if let Some(Err(e)) = self.sync_working_copy() {
+                self.file_status = Some(e);
+                return Ok(());
+            }
+            if let Some(copy) = self.working_copy.take() {
+                copy.close()?;
+            }
+            self.file_status = Some("Closed working copy".to_string());
+            self.file_details = self.get_file_details();
+        }
+        Ok(())
+    }
+
+    pub fn toggle_watch_panel(&mut self) {
+        self.watch_open = !self.watch_open;
+        if self.watch_open {
+            self.recent_open = false;
+        }
+    }
+
+    pub fn load_watch(&mut self, vault: &Vault) {
+        self.watch_panel.load(vault);
+    }
+
+    pub fn save_watch(&mut self, vault: &Vault) {
+        self.watch_panel.save(vault);
+    }
+
+    // Folder the background watcher should currently observe
+    pub fn watched_folder(&self) -> Option<PathBuf> {
+        self.watch_panel.watched_folder()
+    }
+
+    // Whether dropped files are waiting to be encrypted
+    pub fn is_watch_waiting(&self) -> bool {
+        self.watch_panel.is_waiting()
+    }
+
+    pub fn choose_watch_input(&mut self) {
+        self.watch_panel.choose_input();
+    }
 
-            let decryptor = match Decryptor::new(file_content.as_slice()) {
-                Ok(decryptor) => decryptor,
-                Err(e) => {
-                    eprintln!("Failed to create decryptor: {:?}", e);
-                    return;
+    pub fn choose_watch_output(&mut self) {
+        self.watch_panel.choose_output();
+    }
+
+    pub fn watch_recipients_changed(&mut self, value: String) {
+        self.watch_panel.recipients_changed(value);
+    }
+
+    pub fn watch_shred_toggled(&mut self, shred: bool) {
+        self.watch_panel.shred_toggled(shred);
+    }
+
+    pub fn toggle_watching(&mut self) {
+        self.watch_panel.toggle_running();
+    }
+
+    pub fn watched_files_changed(&mut self, paths: Vec<PathBuf>) {
+        self.watch_panel.files_changed(paths);
+    }
+
+    pub fn watch_failed(&mut self, error: String) {
+        self.watch_panel.failed(error);
+    }
+
+    pub fn process_watch_folder(&mut self) {
+        let operations = self.watch_panel.process();
+        self.operations.extend(operations);
+    }
+
+    // Operations finished since the last call, for toasts and the history page
+    pub fn take_operations(&mut self) -> Vec<Operation> {
+        std::mem::take(&mut self.operations)
+    }
+
+    pub fn shred_passes_changed(&mut self, passes: u8) {
+        self.shred_passes = Some(passes);
+    }
+
+    // Destroy the selected file, asking first unless the user turned that off
+    pub fn shred_file(&mut self) -> Option<Confirmation> {
+        let path = self.selected_file.clone()?;
+        let passes = self.shred_passes.unwrap_or(shred::DEFAULT_PASSES);
+        if config::current().confirm.shred {
+            return Some(Confirmation::new(
+                confirm::Action::Shred,
+                format!(
+                    "Permanently destroy {} with {} overwrite passes?\n\n{}",
+                    path.display(),
+                    passes,
+                    shred::WARNING
+                ),
+                Pending::Shred(path, passes),
+            ));
+        }
+        self.shred(&path, passes);
+        None
+    }
+
+    fn shred(&mut self, path: &Path, passes: u8) {
+        let size = file_size(path);
+        let start_time = Instant::now();
+        let result = shred::shred(path, passes);
+        self.operations.push(Operation::new(
+            Kind::Shred,
+            Some(path),
+            size,
+            start_time.elapsed(),
+            &result,
+        ));
+        self.file_status = Some(match result {
+            Ok(()) => {
+                self.selection.retain(|selected| selected != path);
+                self.selected_file = None;
+                self.file_details = None;
+                format!("Shredded {}", path.display())
+            }
+            Err(e) => e,
+        });
+    }
+
+    pub fn toggle_help(&mut self) {
+        self.show_help = !self.show_help;
+    }
+
+    pub fn close_help(&mut self) {
+        self.show_help = false;
+    }
+
+    pub fn backend_selected(&mut self, backend: Backend) {
+        self.backend = backend;
+    }
+
+    // Encrypt every selected file with the chosen backend, returning questions about the rest
+    pub fn encrypt_file(&mut self) -> Vec<Confirmation> {
+        self.batch = Batch::default();
+        self.drop_target.dismiss();
+        let files = self.selection.clone();
+        self.encrypt_files(&files, Allowed::default())
+    }
+
+    pub fn decrypt_file(&mut self) -> Vec<Confirmation> {
+        self.batch = Batch::default();
+        self.drop_target.dismiss();
+        let files = self.selection.clone();
+        self.decrypt_files(&files, Allowed::default())
+    }
+
+    // Carry on with work the user confirmed, which may raise the next question
+    pub fn confirmed(&mut self, pending: Pending) -> Vec<Confirmation> {
+        self.batch = Batch::default();
+        match pending {
+            Pending::Encrypt(files, allowed) => self.encrypt_files(&files, allowed),
+            Pending::Decrypt(files, allowed) => self.decrypt_files(&files, allowed),
+            Pending::Shred(path, passes) => {
+                self.shred(&path, passes);
+                Vec::new()
+            }
+            Pending::DeleteAccount | Pending::Discard(_) => Vec::new(),
+        }
+    }
+
+    fn encrypt_files(&mut self, files: &[PathBuf], allowed: Allowed) -> Vec<Confirmation> {
+        let allowed = allowed.with_settings();
+        let (ready, confirmations) = check(files, true, allowed);
+        let passes = self.shred_passes.unwrap_or(shred::DEFAULT_PASSES);
+        for path in &ready {
+            let size = file_size(path);
+            let start_time = Instant::now();
+            let result = fs::read(path)
+                .map_err(|e| format!("Failed to read file: {:?}", e))
+                .and_then(|content| encrypt_with_backend(&content, self.backend))
+                .and_then(|encrypted| {
+                    write_output(path, &encrypted, true, allowed.overwrite, passes)
+                });
+
+            // Update file details with encryption time when the file was replaced in place
+            if result.as_ref().ok() == Some(path) && self.selected_file.as_ref() == Some(path) {
+                if let Some(details) = &mut self.file_details {
+                    details.cipher = self.backend.to_string();
+                    details.encryption_time = Some(start_time.elapsed());
                 }
+            }
+            self.record(path, Action::Encrypted, size, start_time.elapsed(), result);
+        }
+        if !ready.is_empty() {
+            self.report("Encrypted");
+        }
+        confirmations
+    }
+
+    fn decrypt_files(&mut self, files: &[PathBuf], allowed: Allowed) -> Vec<Confirmation> {
+        let allowed = allowed.with_settings();
+        let (ready, confirmations) = check(files, false, allowed);
+        let passes = self.shred_passes.unwrap_or(shred::DEFAULT_PASSES);
+        for path in &ready {
+            let size = file_size(path);
+            let start_time = Instant::now();
+            let result = if detect_file_backend(path).is_none() {
+                Err(format!("{} is not encrypted", path.display()))
+            } else {
+                fs::read(path)
+                    .map_err(|e| format!("Failed to read file: {:?}", e))
+                    .and_then(|content| decrypt_bytes(&content))
+                    .and_then(|decrypted| {
+                        write_output(path, &decrypted, false, allowed.overwrite, passes)
+                    })
             };
 
-            let mut decrypted_content = Vec::new();
-            match decryptor {
-                Decryptor::Passphrase(decryptor) => {
-                    if let Err(e) = decryptor
-                        .decrypt(&SecretString::new("password".to_string()), None)
-                        .and_then(|mut reader| Ok(reader.read_to_end(&mut decrypted_content)?))
-                    {
-                        eprintln!("Failed to decrypt file: {:?}", e);
-                        return;
-                    }
+            // Update file details with decryption time when the file was replaced in place
+            if result.as_ref().ok() == Some(path) && self.selected_file.as_ref() == Some(path) {
+                if let Some(details) = &mut self.file_details {
+                    details.cipher = "Not encrypted".to_string();
+                    details.decryption_time = Some(start_time.elapsed());
                 }
-                _ => {
-                    eprintln!("Unsupported decryptor");
-                    return;
-                }
-            };
+            }
+            self.record(path, Action::Decrypted, size, start_time.elapsed(), result);
+        }
+        if !ready.is_empty() {
+            self.report("Decrypted");
+        }
+        confirmations
+    }
 
-            if let Err(e) = fs::write(path, decrypted_content) {
-                eprintln!("Failed to write decrypted file: {:?}", e);
-                return;
+    // Count the result for the status line and the history, and list it under the file it produced
+    fn record(
+        &mut self,
+        source: &Path,
+        action: Action,
+        size: Option<u64>,
+        elapsed: Duration,
+        result: Result<PathBuf, String>,
+    ) {
+        let kind = match action {
+            Action::Encrypted => Kind::Encrypt,
+            Action::Decrypted => Kind::Decrypt,
+        };
+        self.operations
+            .push(Operation::new(kind, Some(source), size, elapsed, &result));
+        let (path, result) = match result {
+            Ok(destination) => (destination, Ok(())),
+            Err(e) => (source.to_path_buf(), Err(e)),
+        };
+        self.recent.record(&path, action, &result);
+        match result {
+            Ok(()) => self.batch.done += 1,
+            Err(e) => {
+                self.batch.failed += 1;
+                self.batch.last_error = Some(e);
             }
+        }
+    }
 
-            let duration = start_time.elapsed();
+    fn report(&mut self, action: &str) {
+        let batch = &self.batch;
+        let files = if batch.done == 1 { "file" } else { "files" };
+        self.file_status = Some(match &batch.last_error {
+            Some(e) if batch.failed == 1 && batch.done == 0 => e.clone(),
+            Some(e) => format!(
+                "{} {} {}, {} failed: {}",
+                action, batch.done, files, batch.failed, e
+            ),
+            None => format!("{} {} {}", action, batch.done, files),
+        });
+    }
+}
 
-            // Update file details with decryption time
-            if let Some(details) = &mut self.file_details {
-                details.decryption_time = Some(duration);
-            }
+// Passphrase the store encrypts files with
+const STORE_PASSPHRASE: &str = "password";
+
+// Encrypt bytes with the store passphrase, optionally as armored text
+pub(crate) fn encrypt_bytes(data: &[u8], format: Format) -> Result<Vec<u8>, String> {
+    AgePassphrase::new(STORE_PASSPHRASE, format).encrypt_bytes(data)
+}
+
+// Encrypt bytes with the store passphrase using the given backend, armoring age output when configured
+pub(crate) fn encrypt_with_backend(data: &[u8], backend: Backend) -> Result<Vec<u8>, String> {
+    if backend == Backend::AgePassphrase && config::current().output.armor {
+        return encrypt_bytes(data, Format::AsciiArmor);
+    }
+    backend
+        .with_passphrase(STORE_PASSPHRASE)
+        .encrypt_bytes(data)
+}
+
+// Encrypt bytes to age recipients, falling back to the store passphrase without any
+pub(crate) fn encrypt_to_recipients(data: &[u8], recipients: &[String]) -> Result<Vec<u8>, String> {
+    if recipients.is_empty() {
+        return encrypt_bytes(data, Format::Binary);
+    }
+
+    let mut parsed = Vec::new();
+    for recipient in recipients {
+        parsed.push(
+            recipient
+                .parse::<x25519::Recipient>()
+                .map_err(|e| format!("Invalid recipient {}: {}", recipient, e))?,
+        );
+    }
+    AgeRecipients::new(parsed, Vec::new()).encrypt_bytes(data)
+}
+
+// Decrypt bytes that were encrypted to an age identity
+pub(crate) fn decrypt_with_identity(
+    data: &[u8],
+    identity: &x25519::Identity,
+) -> Result<Vec<u8>, String> {
+    AgeRecipients::new(Vec::new(), vec![identity.clone()]).decrypt_bytes(data)
+}
+
+// Decrypt bytes from any store backend, read from their header
+pub(crate) fn decrypt_bytes(data: &[u8]) -> Result<Vec<u8>, String> {
+    detect_passphrase(data, STORE_PASSPHRASE)?.decrypt_bytes(data)
+}
+
+fn file_size(path: &Path) -> Option<u64> {
+    fs::metadata(path).map(|metadata| metadata.len()).ok()
+}
+
+// Split files into those ready to process and questions about the rest
+fn check(
+    files: &[PathBuf],
+    encrypting: bool,
+    allowed: Allowed,
+) -> (Vec<PathBuf>, Vec<Confirmation>) {
+    let mut ready = Vec::new();
+    let mut encrypted = Vec::new();
+    let mut existing = Vec::new();
+    for path in files {
+        let is_encrypted = detect_file_backend(path).is_some();
+        // Plaintext is refused by decrypt before it could replace anything
+        let writes = encrypting || is_encrypted;
+        if encrypting && is_encrypted && !allowed.double_encrypt {
+            encrypted.push(path.clone());
+        } else if writes && !allowed.overwrite && output_exists(path, encrypting) {
+            existing.push(path.clone());
+        } else {
+            ready.push(path.clone());
+        }
+    }
+
+    let pending = |files| {
+        if encrypting {
+            Pending::Encrypt(files, allowed)
+        } else {
+            Pending::Decrypt(files, allowed)
         }
+    };
+    let mut confirmations = Vec::new();
+    if !encrypted.is_empty() {
+        confirmations.push(Confirmation::for_files(
+            confirm::Action::DoubleEncrypt,
+            pending(encrypted),
+        ));
+    }
+    if !existing.is_empty() {
+        confirmations.push(Confirmation::for_files(
+            confirm::Action::Overwrite,
+            pending(existing),
+        ));
+    }
+    (ready, confirmations)
+}
+
+// Whether writing the result would replace a different file that already exists
+fn output_exists(source: &Path, encrypting: bool) -> bool {
+    output_path(source, encrypting)
+        .is_ok_and(|destination| destination != source && destination.exists())
+}
+
+// Where an encrypted or decrypted file goes under the output settings
+fn output_path(source: &Path, encrypting: bool) -> Result<PathBuf, String> {
+    let output = config::current().output;
+    let name = source
+        .file_name()
+        .ok_or("The file has no name")?
+        .to_string_lossy()
+        .to_string();
+    let directory = match output.directory {
+        Some(directory) => directory,
+        None => source.parent().map(Path::to_path_buf).unwrap_or_default(),
+    };
+
+    // Kept originals need a different name for the result
+    let name = match (output.keep_originals, encrypting) {
+        (false, _) => name,
+        (true, true) => format!("{}.age", name),
+        (true, false) => match name.strip_suffix(".age") {
+            Some(stem) => stem.to_string(),
+            None => format!("{}.decrypted", name),
+        },
+    };
+    Ok(directory.join(name))
+}
+
+// Write an encrypted or decrypted file where the output settings say, returning its path
+fn write_output(
+    source: &Path,
+    data: &[u8],
+    encrypting: bool,
+    overwrite: bool,
+    passes: u8,
+) -> Result<PathBuf, String> {
+    let destination = output_path(source, encrypting)?;
+    if destination != source && destination.exists() && !overwrite {
+        return Err(format!("{} already exists", destination.display()));
     }
+
+    if destination == source {
+        // Move the original aside so it can still be wiped once the result replaced it
+        let mut aside = source.file_name().unwrap_or_default().to_os_string();
+        aside.push(".lockbox-old");
+        let original = source.with_file_name(aside);
+        fs::rename(source, &original)
+            .map_err(|e| format!("Failed to move original file: {:?}", e))?;
+        if let Err(e) = vault::write_atomic(&destination, data) {
+            let _ = fs::rename(&original, source);
+            return Err(e);
+        }
+        remove_original(&original, encrypting, passes)?;
+    } else {
+        vault::write_atomic(&destination, data)?;
+        if !config::current().output.keep_originals {
+            remove_original(source, encrypting, passes)?;
+        }
+    }
+    Ok(destination)
+}
+
+// Plaintext originals are shredded, encrypted ones only need unlinking
+fn remove_original(path: &Path, encrypting: bool, passes: u8) -> Result<(), String> {
+    if encrypting {
+        shred::shred(path, passes)
+    } else {
+        fs::remove_file(path).map_err(|e| format!("Failed to remove original file: {:?}", e))
+    }
+}
+
+// Backend that encrypted a file, judged from its first bytes
+pub(crate) fn detect_file_backend(path: &Path) -> Option<Backend> {
+    let mut header = Vec::new();
+    fs::File::open(path)
+        .and_then(|file| file.take(64).read_to_end(&mut header))
+        .ok()?;
+    Backend::detect(&header)
 }
 
-// Custom button styles remain the same as in the previous implementation
-struct BlueButton;
+// Button and container styles follow the active theme palette
+pub(crate) struct BlueButton;
 
 impl button::StyleSheet for BlueButton {
     fn active(&self) -> button::Style {
+        let palette = theme::palette();
         button::Style {
-            background: Some(Background::Color(Color::from_rgb(
-                66.0 / 255.0,
-                144.0 / 255.0,
-                245.0 / 255.0,
-            ))), // Set to #4290f5
+            background: Some(Background::Color(palette.primary)),
             border_radius: 5.0,
-            text_color: Color::WHITE,
+            text_color: palette.button_text,
             shadow_offset: iced::Vector::new(0.0, 0.0),
             ..button::Style::default()
         }
@@ -302,20 +1397,21 @@ impl button::StyleSheet for BlueButton {
 
     fn hovered(&self) -> button::Style {
         button::Style {
-            background: Some(Background::Color(Color::from_rgb(0.5, 0.5, 0.5))), // Darker blue on hover
+            background: Some(Background::Color(theme::palette().primary_hover)),
             ..self.active()
         }
     }
 }
 
-struct GreenButton;
+pub(crate) struct GreenButton;
 
 impl button::StyleSheet for GreenButton {
     fn active(&self) -> button::Style {
+        let palette = theme::palette();
         button::Style {
-            background: Some(Background::Color(Color::from_rgb(0.52, 0.72, 0.59))), // #84b896
+            background: Some(Background::Color(palette.success)),
             border_radius: 5.0,
-            text_color: Color::WHITE,
+            text_color: palette.button_text,
             shadow_offset: iced::Vector::new(0.0, 0.0),
             ..button::Style::default()
         }
@@ -323,20 +1419,21 @@ impl button::StyleSheet for GreenButton {
 
     fn hovered(&self) -> button::Style {
         button::Style {
-            background: Some(Background::Color(Color::from_rgb(0.42, 0.62, 0.49))), // Darker green on hover
+            background: Some(Background::Color(theme::palette().success_hover)),
             ..self.active()
         }
     }
 }
 
-struct OrangeButton;
+pub(crate) struct OrangeButton;
 
 impl button::StyleSheet for OrangeButton {
     fn active(&self) -> button::Style {
+        let palette = theme::palette();
         button::Style {
-            background: Some(Background::Color(Color::from_rgb(0.96, 0.69, 0.36))), // #f5af5b
+            background: Some(Background::Color(palette.warning)),
             border_radius: 5.0,
-            text_color: Color::WHITE,
+            text_color: palette.button_text,
             shadow_offset: iced::Vector::new(0.0, 0.0),
             ..button::Style::default()
         }
@@ -344,21 +1441,20 @@ impl button::StyleSheet for OrangeButton {
 
     fn hovered(&self) -> button::Style {
         button::Style {
-            background: Some(Background::Color(Color::from_rgb(0.86, 0.59, 0.26))), // Darker orange on hover
+            background: Some(Background::Color(theme::palette().warning_hover)),
             ..self.active()
         }
     }
 }
 
-// Add these struct definitions near your other style structs
-struct BlueBorderContainer;
-struct AlternateRowDark;
+pub(crate) struct BlueBorderContainer;
+pub(crate) struct AlternateRowDark;
 
 impl container::StyleSheet for BlueBorderContainer {
     fn style(&self) -> container::Style {
         container::Style {
             border_width: 1.0,
-            border_color: Color::from_rgb(66.0 / 255.0, 144.0 / 255.0, 245.0 / 255.0),
+            border_color: theme::palette().border,
             border_radius: 5.0, // Adjust the value as needed
             ..Default::default()
         }
@@ -368,7 +1464,7 @@ impl container::StyleSheet for BlueBorderContainer {
 impl container::StyleSheet for AlternateRowDark {
     fn style(&self) -> container::Style {
         container::Style {
-            background: Some(Background::Color(Color::from_rgb(0.95, 0.95, 0.95))), // Light grey
+            background: Some(Background::Color(theme::palette().surface)),
             border_width: 1.0,
             border_radius: 0.0,
             ..container::Style::default()
@@ -0,0 +1,171 @@
+// src/theme.rs
+
+use iced::{container, Background, Color};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+// Theme every stylesheet reads from, stored as its index in Theme::ALL
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+// Palettes the interface can be drawn with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Theme {
+    Light,
+    Dark,
+    HighContrast,
+}
+
+impl Theme {
+    const ALL: [Theme; 3] = [Theme::Light, Theme::Dark, Theme::HighContrast];
+
+    pub fn palette(self) -> Palette {
+        match self {
+            Theme::Light => LIGHT,
+            Theme::Dark => DARK,
+            Theme::HighContrast => HIGH_CONTRAST,
+        }
+    }
+}
+
+// Theme choice offered in settings, including following the system
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ThemeSetting {
+    #[default]
+    System,
+    Light,
+    Dark,
+    HighContrast,
+}
+
+impl ThemeSetting {
+    pub const ALL: [ThemeSetting; 4] = [
+        ThemeSetting::System,
+        ThemeSetting::Light,
+        ThemeSetting::Dark,
+        ThemeSetting::HighContrast,
+    ];
+
+    // Concrete theme, asking the OS when following the system
+    pub fn resolve(self) -> Theme {
+        match self {
+            ThemeSetting::System => match dark_light::detect() {
+                dark_light::Mode::Dark => Theme::Dark,
+                dark_light::Mode::Light | dark_light::Mode::Default => Theme::Light,
+            },
+            ThemeSetting::Light => Theme::Light,
+            ThemeSetting::Dark => Theme::Dark,
+            ThemeSetting::HighContrast => Theme::HighContrast,
+        }
+    }
+}
+
+impl fmt::Display for ThemeSetting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ThemeSetting::System => "Follow system",
+            ThemeSetting::Light => "Light",
+            ThemeSetting::Dark => "Dark",
+            ThemeSetting::HighContrast => "High contrast",
+        })
+    }
+}
+
+// Colors shared by every stylesheet
+#[derive(Debug, Clone, Copy)]
+pub struct Palette {
+    pub background: Color,
+    pub text: Color,
+    pub surface: Color,
+    pub border: Color,
+    pub primary: Color,
+    pub primary_hover: Color,
+    pub success: Color,
+    pub success_hover: Color,
+    pub warning: Color,
+    pub warning_hover: Color,
+    pub button_text: Color,
+    pub input_background: Color,
+    pub input_text: Color,
+    pub placeholder: Color,
+}
+
+const LIGHT: Palette = Palette {
+    background: Color::WHITE,
+    text: Color::BLACK,
+    surface: Color::from_rgb(0.95, 0.95, 0.95),
+    border: Color::from_rgb(66.0 / 255.0, 144.0 / 255.0, 245.0 / 255.0),
+    primary: Color::from_rgb(66.0 / 255.0, 144.0 / 255.0, 245.0 / 255.0), // #4290f5
+    primary_hover: Color::from_rgb(0.5, 0.5, 0.5),
+    success: Color::from_rgb(0.52, 0.72, 0.59), // #84b896
+    success_hover: Color::from_rgb(0.42, 0.62, 0.49),
+    warning: Color::from_rgb(0.96, 0.69, 0.36), // #f5af5b
+    warning_hover: Color::from_rgb(0.86, 0.59, 0.26),
+    button_text: Color::WHITE,
+    input_background: Color::WHITE,
+    input_text: Color::BLACK,
+    placeholder: Color::from_rgb(0.5, 0.5, 0.5),
+};
+
+const DARK: Palette = Palette {
+    background: Color::from_rgb(0.12, 0.12, 0.14),
+    text: Color::from_rgb(0.9, 0.9, 0.9),
+    surface: Color::from_rgb(0.18, 0.18, 0.21),
+    border: Color::from_rgb(0.32, 0.5, 0.8),
+    primary: Color::from_rgb(0.26, 0.48, 0.82),
+    primary_hover: Color::from_rgb(0.35, 0.35, 0.38),
+    success: Color::from_rgb(0.33, 0.56, 0.41),
+    success_hover: Color::from_rgb(0.27, 0.46, 0.34),
+    warning: Color::from_rgb(0.8, 0.52, 0.2),
+    warning_hover: Color::from_rgb(0.68, 0.43, 0.15),
+    button_text: Color::WHITE,
+    input_background: Color::from_rgb(0.2, 0.2, 0.23),
+    input_text: Color::from_rgb(0.9, 0.9, 0.9),
+    placeholder: Color::from_rgb(0.55, 0.55, 0.58),
+};
+
+const HIGH_CONTRAST: Palette = Palette {
+    background: Color::BLACK,
+    text: Color::WHITE,
+    surface: Color::BLACK,
+    border: Color::WHITE,
+    primary: Color::from_rgb(1.0, 1.0, 0.0),
+    primary_hover: Color::WHITE,
+    success: Color::from_rgb(0.0, 1.0, 0.5),
+    success_hover: Color::WHITE,
+    warning: Color::from_rgb(1.0, 0.6, 0.0),
+    warning_hover: Color::WHITE,
+    button_text: Color::BLACK,
+    input_background: Color::BLACK,
+    input_text: Color::WHITE,
+    placeholder: Color::from_rgb(0.75, 0.75, 0.75),
+};
+
+pub fn current() -> Theme {
+    Theme::ALL[CURRENT.load(Ordering::Relaxed) as usize]
+}
+
+pub fn palette() -> Palette {
+    current().palette()
+}
+
+// Switch every stylesheet over to a theme
+pub fn apply(setting: ThemeSetting) {
+    let theme = setting.resolve();
+    let index = Theme::ALL.iter().position(|t| *t == theme).unwrap_or(0);
+    CURRENT.store(index as u8, Ordering::Relaxed);
+}
+
+// Background and default text color for a whole page
+pub struct PageContainer;
+
+impl container::StyleSheet for PageContainer {
+    fn style(&self) -> container::Style {
+        let palette = palette();
+        container::Style {
+            background: Some(Background::Color(palette.background)),
+            text_color: Some(palette.text),
+            ..container::Style::default()
+        }
+    }
+}
@@ -0,0 +1,127 @@
+// src/toast.rs
+
+use crate::history::Operation;
+use crate::theme;
+use iced::{
+    button, container, Alignment, Background, Button, Column, Container, Element, Length, Row, Text,
+};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+// How long toasts stay up, failures longer so the error can be read
+const SUCCESS_TIMEOUT: Duration = Duration::from_secs(4);
+const FAILURE_TIMEOUT: Duration = Duration::from_secs(8);
+
+// Toasts shown at once, older ones are dropped first
+const MAX_TOASTS: usize = 4;
+
+struct Toast {
+    id: u64,
+    text: String,
+    failed: bool,
+    shown_at: Instant,
+    dismiss_button: button::State,
+}
+
+// Short-lived notices along the bottom of the window
+#[derive(Default)]
+pub struct Toasts {
+    toasts: VecDeque<Toast>,
+    next_id: u64,
+}
+
+impl Toasts {
+    pub fn is_empty(&self) -> bool {
+        self.toasts.is_empty()
+    }
+
+    pub fn push(&mut self, operation: &Operation) {
+        self.show(operation.summary(), operation.failed());
+    }
+
+    // Problems that are not file operations, such as failed saves
+    pub fn error(&mut self, text: String) {
+        self.show(text, true);
+    }
+
+    fn show(&mut self, text: String, failed: bool) {
+        self.toasts.push_back(Toast {
+            id: self.next_id,
+            text,
+            failed,
+            shown_at: Instant::now(),
+            dismiss_button: button::State::new(),
+        });
+        self.next_id += 1;
+        while self.toasts.len() > MAX_TOASTS {
+            self.toasts.pop_front();
+        }
+    }
+
+    pub fn expire(&mut self) {
+        self.toasts.retain(|toast| {
+            let timeout = if toast.failed {
+                FAILURE_TIMEOUT
+            } else {
+                SUCCESS_TIMEOUT
+            };
+            toast.shown_at.elapsed() < timeout
+        });
+    }
+
+    pub fn dismiss(&mut self, id: u64) {
+        self.toasts.retain(|toast| toast.id != id);
+    }
+
+    pub fn clear(&mut self) {
+        self.toasts.clear();
+    }
+
+    pub fn view(&mut self) -> Element<'_, crate::Message> {
+        let mut column = Column::new()
+            .spacing(5)
+            .padding(10)
+            .width(Length::Fill)
+            .align_items(Alignment::End);
+        for toast in &mut self.toasts {
+            let row = Row::new()
+                .spacing(10)
+                .align_items(Alignment::Center)
+                .push(Text::new(&toast.text).size(14))
+                .push(
+                    Button::new(&mut toast.dismiss_button, Text::new("x").size(14))
+                        .padding(2)
+                        .on_press(crate::Message::DismissToast(toast.id)),
+                );
+            column = column.push(
+                Container::new(row)
+                    .style(ToastContainer {
+                        failed: toast.failed,
+                    })
+                    .padding(8)
+                    .max_width(500),
+            );
+        }
+        column.into()
+    }
+}
+
+struct ToastContainer {
+    failed: bool,
+}
+
+impl container::StyleSheet for ToastContainer {
+    fn style(&self) -> container::Style {
+        let palette = theme::palette();
+        container::Style {
+            background: Some(Background::Color(if self.failed {
+                palette.warning
+            } else {
+                palette.success
+            })),
+            text_color: Some(palette.button_text),
+            border_radius: 5.0,
+            ..container::Style::default()
+        }
+    }
+}
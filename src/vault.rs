@@ -0,0 +1,232 @@
+// src/vault.rs
+
+use age::secrecy::{ExposeSecret, SecretString};
+use age::{x25519, Decryptor, Encryptor};
+use dirs::config_dir;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+// Name of the file holding the user's identity, encrypted with their password
+const IDENTITY_FILE: &str = "identity.age";
+
+// Public recipient of the identity, kept in the clear so recovery can check a key
+const RECIPIENT_FILE: &str = "recipient.txt";
+
+// An unlocked per-user vault. Everything it stores is encrypted to the user's
+// age identity in memory before it is written, so plaintext never hits disk.
+pub struct Vault {
+    dir: PathBuf,
+    identity: x25519::Identity,
+}
+
+impl Vault {
+    // Unlock the vault for a user, creating their identity on first use
+    pub fn unlock(username: &str, password: &str) -> Result<Vault, String> {
+        let dir = user_dir(username)?;
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create vault: {:?}", e))?;
+
+        let identity_path = dir.join(IDENTITY_FILE);
+        let identity = if identity_path.exists() {
+            let encrypted = fs::read(&identity_path)
+                .map_err(|e| format!("Failed to read vault identity: {:?}", e))?;
+            let decrypted = decrypt_with_passphrase(&encrypted, password)?;
+            String::from_utf8(decrypted)
+                .map_err(|_| "Vault identity is corrupted".to_string())?
+                .trim()
+                .parse::<x25519::Identity>()
+                .map_err(|e| format!("Vault identity is corrupted: {}", e))?
+        } else {
+            let identity = x25519::Identity::generate();
+            let encrypted =
+                encrypt_with_passphrase(identity.to_string().expose_secret().as_bytes(), password)?;
+            write_atomic(&identity_path, &encrypted)?;
+            identity
+        };
+
+        // Vaults created before the recipient was recorded get it on their next unlock
+        let recipient_path = dir.join(RECIPIENT_FILE);
+        if !recipient_path.exists() {
+            write_atomic(&recipient_path, identity.to_public().to_string().as_bytes())?;
+        }
+
+        Ok(Vault { dir, identity })
+    }
+
+    // Re-encrypt a recovered identity under a new password, checking it against
+    // the recipient recorded when the vault was unlocked
+    pub fn recover(username: &str, identity: &str, new_password: &str) -> Result<(), String> {
+        let dir = user_dir(username)?;
+        let identity = identity
+            .trim()
+            .parse::<x25519::Identity>()
+            .map_err(|_| "The recovered key is not valid".to_string())?;
+        if !dir.join(IDENTITY_FILE).exists() {
+            return Err(format!("No vault found for {}", username));
+        }
+        let vault = Vault { dir, identity };
+
+        // Nothing on disk changes unless the key provably belongs to this vault
+        match fs::read_to_string(vault.dir.join(RECIPIENT_FILE)) {
+            Ok(recipient) => {
+                if recipient.trim() != vault.identity.to_public().to_string() {
+                    return Err("The recovered key does not belong to this vault".to_string());
+                }
+            }
+            Err(_) => vault.check_records()?,
+        }
+
+        let encrypted = encrypt_with_passphrase(
+            vault.identity.to_string().expose_secret().as_bytes(),
+            new_password,
+        )?;
+        write_atomic(&vault.dir.join(IDENTITY_FILE), &encrypted)
+    }
+
+    // Without a recorded recipient a record decrypting is the only proof of the key
+    fn check_records(&self) -> Result<(), String> {
+        let record =
+            self.records()?.into_iter().next().ok_or(
+                "This vault cannot check the recovered key, log in once to enable recovery",
+            )?;
+        let encrypted = fs::read(self.record_path(&record))
+            .map_err(|e| format!("Failed to read vault: {:?}", e))?;
+        self.decrypt(&encrypted)
+            .map(|_| ())
+            .map_err(|_| "The recovered key does not belong to this vault".to_string())
+    }
+
+    // A vault with a fresh identity in `dir`, for tests
+    #[cfg(test)]
+    pub(crate) fn temporary(dir: &Path) -> Vault {
+        Vault {
+            dir: dir.to_path_buf(),
+            identity: x25519::Identity::generate(),
+        }
+    }
+
+    // The identity backing this vault, for recovery setups
+    pub fn identity_secret(&self) -> SecretString {
+        self.identity.to_string()
+    }
+
+    // Names of the records stored in this vault
+    pub fn records(&self) -> Result<Vec<String>, String> {
+        let mut names: Vec<String> = fs::read_dir(&self.dir)
+            .map_err(|e| format!("Failed to read vault: {:?}", e))?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension().is_some_and(|ext| ext == "age")
+                    && path.file_name().is_some_and(|name| name != IDENTITY_FILE)
+            })
+            .filter_map(|path| Some(path.file_stem()?.to_string_lossy().to_string()))
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+
+    // Load a named record, returning the default value if it was never saved
+    pub fn load<T: DeserializeOwned + Default>(&self, name: &str) -> Result<T, String> {
+        let path = self.record_path(name);
+        if !path.exists() {
+            return Ok(T::default());
+        }
+
+        let encrypted = fs::read(&path).map_err(|e| format!("Failed to read {}: {:?}", name, e))?;
+        let decrypted = self.decrypt(&encrypted)?;
+        serde_json::from_slice(&decrypted).map_err(|e| format!("Failed to parse {}: {:?}", name, e))
+    }
+
+    // Encrypt a named record in memory and write the ciphertext to disk
+    pub fn save<T: Serialize>(&self, name: &str, value: &T) -> Result<(), String> {
+        let plaintext = serde_json::to_vec(value)
+            .map_err(|e| format!("Failed to serialize {}: {:?}", name, e))?;
+        let encrypted = self.encrypt(&plaintext)?;
+        write_atomic(&self.record_path(name), &encrypted)
+    }
+
+    // Encrypt bytes to the vault identity
+    pub fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        let encryptor = Encryptor::with_recipients(vec![Box::new(self.identity.to_public())]);
+        let mut encrypted = Vec::new();
+        let mut writer = encryptor
+            .wrap_output(&mut encrypted)
+            .map_err(|e| format!("Failed to create encryptor: {:?}", e))?;
+        writer
+            .write_all(data)
+            .map_err(|e| format!("Failed to encrypt data: {:?}", e))?;
+        writer
+            .finish()
+            .map_err(|e| format!("Failed to finalize encryption: {:?}", e))?;
+        Ok(encrypted)
+    }
+
+    // Decrypt bytes that were encrypted to the vault identity
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        let decryptor = match Decryptor::new(data) {
+            Ok(Decryptor::Recipients(decryptor)) => decryptor,
+            Ok(_) => return Err("Unsupported decryptor".to_string()),
+            Err(e) => return Err(format!("Failed to create decryptor: {:?}", e)),
+        };
+
+        let mut decrypted = Vec::new();
+        decryptor
+            .decrypt(std::iter::once(&self.identity as &dyn age::Identity))
+            .and_then(|mut reader| Ok(reader.read_to_end(&mut decrypted)?))
+            .map_err(|e| format!("Failed to decrypt data: {:?}", e))?;
+        Ok(decrypted)
+    }
+
+    fn record_path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.age", name))
+    }
+}
+
+// Directory holding a user's encrypted data
+pub fn user_dir(username: &str) -> Result<PathBuf, String> {
+    let config_dir = config_dir().ok_or("Failed to get config directory")?;
+    Ok(config_dir.join("lockbox").join("users").join(username))
+}
+
+fn encrypt_with_passphrase(data: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+    let encryptor = Encryptor::with_user_passphrase(SecretString::new(passphrase.to_string()));
+    let mut encrypted = Vec::new();
+    let mut writer = encryptor
+        .wrap_output(&mut encrypted)
+        .map_err(|e| format!("Failed to create encryptor: {:?}", e))?;
+    writer
+        .write_all(data)
+        .map_err(|e| format!("Failed to encrypt data: {:?}", e))?;
+    writer
+        .finish()
+        .map_err(|e| format!("Failed to finalize encryption: {:?}", e))?;
+    Ok(encrypted)
+}
+
+fn decrypt_with_passphrase(data: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+    let decryptor = match Decryptor::new(data) {
+        Ok(Decryptor::Passphrase(decryptor)) => decryptor,
+        Ok(_) => return Err("Unsupported decryptor".to_string()),
+        Err(e) => return Err(format!("Failed to create decryptor: {:?}", e)),
+    };
+
+    let mut decrypted = Vec::new();
+    decryptor
+        .decrypt(&SecretString::new(passphrase.to_string()), None)
+        .and_then(|mut reader| Ok(reader.read_to_end(&mut decrypted)?))
+        .map_err(|e| format!("Failed to decrypt data: {:?}", e))?;
+    Ok(decrypted)
+}
+
+// Write through a temporary file so a crash never leaves a half-written file
+pub(crate) fn write_atomic(path: &Path, data: &[u8]) -> Result<(), String> {
+    // Appended rather than replacing the extension, so a user's own .tmp file survives
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+    fs::write(&tmp_path, data).map_err(|e| format!("Failed to write {:?}: {:?}", path, e))?;
+    fs::rename(&tmp_path, path).map_err(|e| format!("Failed to write {:?}: {:?}", path, e))
+}
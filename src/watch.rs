@@ -0,0 +1,436 @@
+// src/watch.rs
+
+use crate::history::{Kind, Operation};
+use crate::session;
+use crate::shred;
+use crate::store::{
+    encrypt_to_recipients, AlternateRowDark, BlueButton, GreenButton, OrangeButton,
+};
+use crate::vault::Vault;
+use iced::futures::channel::mpsc;
+use iced::futures::{future, StreamExt};
+use iced::{
+    button, scrollable, text_input, Button, Checkbox, Color, Column, Container, Element, Length,
+    Scrollable, Subscription, Text, TextInput,
+};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+// Vault record holding the watch folder settings
+const WATCH_RECORD: &str = "watch";
+
+// Files must be left alone this long before they are encrypted
+const SETTLE_TIME: Duration = Duration::from_secs(2);
+
+// Number of activity log lines kept
+const LOG_LIMIT: usize = 100;
+
+// Persisted watch folder settings
+#[derive(Default, Serialize, Deserialize)]
+struct WatchConfig {
+    input: Option<PathBuf>,
+    output: Option<PathBuf>,
+    recipients: String,
+    shred: bool,
+}
+
+// Watch `input` for new or changed files in the background
+pub fn watch(input: PathBuf) -> Subscription<crate::Message> {
+    iced_native::subscription::unfold(
+        input.clone(),
+        WatchState::Starting(input),
+        |state| async move {
+            match state {
+                WatchState::Starting(input) => {
+                    let (sender, receiver) = mpsc::unbounded();
+                    let watcher = notify::recommended_watcher(move |event| {
+                        let _ = sender.unbounded_send(event);
+                    })
+                    .and_then(|mut watcher| {
+                        watcher
+                            .watch(&input, RecursiveMode::NonRecursive)
+                            .map(|_| watcher)
+                    });
+
+                    match watcher {
+                        Ok(watcher) => (None, WatchState::Watching(watcher, receiver)),
+                        Err(e) => (
+                            Some(crate::Message::WatchFailed(format!(
+                                "Failed to watch folder: {}",
+                                e
+                            ))),
+                            WatchState::Stopped,
+                        ),
+                    }
+                }
+                WatchState::Watching(watcher, mut receiver) => match receiver.next().await {
+                    Some(Ok(event)) => {
+                        let message = match event.kind {
+                            EventKind::Create(_) | EventKind::Modify(_) => {
+                                Some(crate::Message::WatchedFilesChanged(event.paths))
+                            }
+                            _ => None,
+                        };
+                        (message, WatchState::Watching(watcher, receiver))
+                    }
+                    Some(Err(e)) => (
+                        Some(crate::Message::WatchFailed(format!("Watcher error: {}", e))),
+                        WatchState::Watching(watcher, receiver),
+                    ),
+                    None => (None, WatchState::Stopped),
+                },
+                WatchState::Stopped => future::pending().await,
+            }
+        },
+    )
+}
+
+enum WatchState {
+    Starting(PathBuf),
+    Watching(
+        RecommendedWatcher,
+        mpsc::UnboundedReceiver<notify::Result<notify::Event>>,
+    ),
+    Stopped,
+}
+
+// Define the state of the watch folder panel on the store page
+#[derive(Default)]
+pub struct WatchPanel {
+    config: WatchConfig,
+    running: bool,
+    pending: HashMap<PathBuf, Instant>,
+    log: VecDeque<String>,
+    scroll: scrollable::State,
+    recipients_input: text_input::State,
+    input_button: button::State,
+    output_button: button::State,
+    start_button: button::State,
+}
+
+impl WatchPanel {
+    pub fn load(&mut self, vault: &Vault) {
+        *self = Self::default();
+        match vault.load(WATCH_RECORD) {
+            Ok(config) => self.config = config,
+            Err(e) => self.log(e),
+        }
+    }
+
+    pub fn save(&mut self, vault: &Vault) {
+        if let Err(e) = vault.save(WATCH_RECORD, &self.config) {
+            self.log(e);
+        }
+    }
+
+    // Folder to watch while the watcher is running
+    pub fn watched_folder(&self) -> Option<PathBuf> {
+        self.config.input.clone().filter(|_| self.running)
+    }
+
+    pub fn is_waiting(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    pub fn choose_input(&mut self) {
+        if let Some(folder) = session::open_dialog().pick_folder() {
+            session::remember_open(&folder);
+            self.config.input = Some(folder);
+            self.running = false;
+        }
+    }
+
+    pub fn choose_output(&mut self) {
+        if let Some(folder) = session::output_dialog().pick_folder() {
+            session::remember_output(&folder);
+            self.config.output = Some(folder);
+        }
+    }
+
+    pub fn recipients_changed(&mut self, value: String) {
+        self.config.recipients = value;
+    }
+
+    pub fn shred_toggled(&mut self, shred: bool) {
+        self.config.shred = shred;
+    }
+
+    pub fn toggle_running(&mut self) {
+        if self.running {
+            self.running = false;
+            self.pending.clear();
+            self.log("Stopped watching".to_string());
+            return;
+        }
+
+        match (&self.config.input, &self.config.output) {
+            (Some(input), Some(output)) if input == output => {
+                self.log("The output folder must differ from the watched folder".to_string())
+            }
+            (Some(input), Some(_)) => {
+                let message = format!("Watching {}", input.display());
+                self.running = true;
+                self.log(message);
+            }
+            _ => self.log("Choose a watched folder and an output folder first".to_string()),
+        }
+    }
+
+    pub fn failed(&mut self, error: String) {
+        self.running = false;
+        self.log(error);
+    }
+
+    // Remember changed files until they have settled
+    pub fn files_changed(&mut self, paths: Vec<PathBuf>) {
+        if !self.running {
+            return;
+        }
+        for path in paths {
+            if path.is_file() && !is_hidden(&path) {
+                self.pending.insert(path, Instant::now());
+            }
+        }
+    }
+
+    // Encrypt settled files, returning what happened to each for the history
+    pub fn process(&mut self) -> Vec<Operation> {
+        let settled: Vec<PathBuf> = self
+            .pending
+            .iter()
+            .filter(|(_, changed)| changed.elapsed() >= SETTLE_TIME)
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        let mut operations = Vec::new();
+        for path in settled {
+            self.pending.remove(&path);
+            let size = match fs::metadata(&path) {
+                Ok(metadata) if metadata.is_file() => metadata.len(),
+                _ => continue,
+            };
+            let start_time = Instant::now();
+            let result = self.encrypt(&path);
+            operations.push(Operation::new(
+                Kind::WatchEncrypt,
+                Some(&path),
+                Some(size),
+                start_time.elapsed(),
+                &result,
+            ));
+            let message = match result {
+                Ok(dest) => format!("Encrypted {} to {}", file_name(&path), file_name(&dest)),
+                Err(e) => format!("{}: {}", file_name(&path), e),
+            };
+            self.log(message);
+        }
+        operations
+    }
+
+    fn encrypt(&self, path: &Path) -> Result<PathBuf, String> {
+        let output = self
+            .config
+            .output
+            .as_ref()
+            .ok_or("No output folder chosen")?;
+        let recipients: Vec<String> = self
+            .config
+            .recipients
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|recipient| !recipient.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        let plaintext = fs::read(path).map_err(|e| format!("Failed to read file: {:?}", e))?;
+        let encrypted = encrypt_to_recipients(&plaintext, &recipients)?;
+
+        // Never overwrite an earlier result with the same name
+        let mut dest = output.join(format!("{}.age", file_name(path)));
+        let mut counter = 1;
+        while dest.exists() {
+            dest = output.join(format!("{}-{}.age", file_name(path), counter));
+            counter += 1;
+        }
+
+        // Write through a hidden temp file so the output folder never holds half a file
+        let tmp = output.join(format!(".{}.lockbox-tmp", file_name(path)));
+        fs::write(&tmp, encrypted)
+            .and_then(|_| fs::rename(&tmp, &dest))
+            .map_err(|e| {
+                let _ = fs::remove_file(&tmp);
+                format!("Failed to write encrypted file: {:?}", e)
+            })?;
+
+        // The file moves to the output folder, so the plaintext goes once it is safe there
+        if self.config.shred {
+            shred::shred(path, shred::DEFAULT_PASSES)?;
+        } else {
+            fs::remove_file(path).map_err(|e| format!("Failed to remove plaintext: {:?}", e))?;
+        }
+        Ok(dest)
+    }
+
+    fn log(&mut self, line: String) {
+        self.log.push_front(line);
+        self.log.truncate(LOG_LIMIT);
+    }
+
+    pub fn view(&mut self) -> Element<'_, crate::Message> {
+        let folder_label = |folder: &Option<PathBuf>, fallback: &str| match folder {
+            Some(folder) => folder.to_string_lossy().to_string(),
+            None => fallback.to_string(),
+        };
+
+        let start_label = if self.running {
+            "Stop Watching"
+        } else {
+            "Start Watching"
+        };
+        let start_style: Box<dyn button::StyleSheet> = if self.running {
+            Box::new(OrangeButton)
+        } else {
+            Box::new(GreenButton)
+        };
+
+        let mut log = Scrollable::new(&mut self.scroll)
+            .spacing(5)
+            .height(Length::Units(120));
+        for line in &self.log {
+            log = log.push(Text::new(line).size(14));
+        }
+
+        Column::new()
+            .spacing(10)
+            .width(Length::Units(500))
+            .push(Text::new("Watch Folder").size(24))
+            .push(
+                Button::new(
+                    &mut self.input_button,
+                    Text::new(folder_label(&self.config.input, "Choose Watched Folder")).size(16),
+                )
+                .style(BlueButton)
+                .on_press(crate::Message::ChooseWatchInput),
+            )
+            .push(
+                Button::new(
+                    &mut self.output_button,
+                    Text::new(folder_label(&self.config.output, "Choose Output Folder")).size(16),
+                )
+                .style(BlueButton)
+                .on_press(crate::Message::ChooseWatchOutput),
+            )
+            .push(
+                TextInput::new(
+                    &mut self.recipients_input,
+                    "age1... recipients (empty uses the store passphrase)",
+                    &self.config.recipients,
+                    crate::Message::WatchRecipientsChanged,
+                )
+                .padding(8)
+                .size(14),
+            )
+            .push(
+                Checkbox::new(
+                    self.config.shred,
+                    "Shred plaintext instead of deleting it",
+                    crate::Message::ToggleWatchShred,
+                )
+                .size(16),
+            )
+            .push(
+                Button::new(&mut self.start_button, Text::new(start_label).size(16))
+                    .style(start_style)
+                    .on_press(crate::Message::ToggleWatching),
+            )
+            .push(Text::new("Activity").size(18))
+            .push(Container::new(log).style(AlternateRowDark).padding(5))
+            .push(
+                Text::new(shred::WARNING)
+                    .size(12)
+                    .color(Color::from_rgb(0.5, 0.5, 0.5)),
+            )
+            .into()
+    }
+}
+
+// Editors and downloads use hidden temp files that should not be picked up
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .map(|name| name.to_string_lossy().starts_with('.'))
+        .unwrap_or(true)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::decrypt_with_identity;
+    use age::x25519;
+
+    fn panel(output: &Path, identity: &x25519::Identity) -> WatchPanel {
+        WatchPanel {
+            config: WatchConfig {
+                output: Some(output.to_path_buf()),
+                recipients: identity.to_public().to_string(),
+                ..WatchConfig::default()
+            },
+            ..WatchPanel::default()
+        }
+    }
+
+    #[test]
+    fn encrypting_moves_the_file_to_the_output_folder() {
+        let (input, output) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let identity = x25519::Identity::generate();
+        let source = input.path().join("report.txt");
+        fs::write(&source, b"quarterly numbers").unwrap();
+
+        let dest = panel(output.path(), &identity).encrypt(&source).unwrap();
+        assert_eq!(dest, output.path().join("report.txt.age"));
+        assert!(!source.exists());
+        let encrypted = fs::read(&dest).unwrap();
+        assert_eq!(
+            decrypt_with_identity(&encrypted, &identity).unwrap(),
+            b"quarterly numbers"
+        );
+        // Only the result is left, no temp file
+        assert_eq!(fs::read_dir(output.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn earlier_results_are_not_overwritten() {
+        let (input, output) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let identity = x25519::Identity::generate();
+        let source = input.path().join("report.txt");
+        fs::write(output.path().join("report.txt.age"), b"earlier").unwrap();
+        fs::write(&source, b"new").unwrap();
+
+        let dest = panel(output.path(), &identity).encrypt(&source).unwrap();
+        assert_eq!(dest, output.path().join("report.txt-1.age"));
+        assert_eq!(
+            fs::read(output.path().join("report.txt.age")).unwrap(),
+            b"earlier"
+        );
+    }
+
+    #[test]
+    fn failed_encryption_keeps_the_plaintext() {
+        let (input, output) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let source = input.path().join("report.txt");
+        fs::write(&source, b"keep me").unwrap();
+
+        let mut panel = panel(output.path(), &x25519::Identity::generate());
+        panel.config.recipients = "not-a-recipient".to_string();
+        assert!(panel.encrypt(&source).is_err());
+        assert_eq!(fs::read(&source).unwrap(), b"keep me");
+    }
+}
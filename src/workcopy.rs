@@ -0,0 +1,149 @@
+// src/workcopy.rs
+
+use crate::cipher::Backend;
+use crate::shred;
+use crate::store::{decrypt_bytes, encrypt_with_backend};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use std::fs::{self, DirBuilder, OpenOptions};
+use std::io::Write;
+#[cfg(unix)]
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+// A decrypted copy of an encrypted file, open in an external editor
+pub struct WorkingCopy {
+    source: PathBuf,
+    dir: PathBuf,
+    file: PathBuf,
+    modified: Option<SystemTime>,
+    backend: Backend,
+}
+
+impl WorkingCopy {
+    // Decrypt `source` into a private temp directory and open it with the default handler
+    pub fn open(source: &Path) -> Result<WorkingCopy, String> {
+        let encrypted = fs::read(source).map_err(|e| format!("Failed to read file: {:?}", e))?;
+        let plaintext = decrypt_bytes(&encrypted)?;
+
+        let mut name = [0u8; 8];
+        OsRng.fill_bytes(&mut name);
+        let dir = temp_root().join(format!("lockbox-{}", hex::encode(name)));
+        let mut builder = DirBuilder::new();
+        #[cfg(unix)]
+        builder.mode(0o700);
+        builder
+            .create(&dir)
+            .map_err(|e| format!("Failed to create temp directory: {:?}", e))?;
+
+        // Drop a trailing .age so the handler sees the real file type
+        let file_name = match source.extension() {
+            Some(ext) if ext == "age" => source.file_stem(),
+            _ => source.file_name(),
+        }
+        .unwrap_or_default();
+        let file = dir.join(file_name);
+
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+        let written = options
+            .open(&file)
+            .and_then(|mut handle| handle.write_all(&plaintext))
+            .map_err(|e| format!("Failed to write working copy: {:?}", e));
+
+        let copy = WorkingCopy {
+            source: source.to_path_buf(),
+            modified: modified(&file),
+            // Saves are encrypted with the same backend as the original
+            backend: Backend::detect(&encrypted).unwrap_or_default(),
+            dir,
+            file,
+        };
+        // Dropping the copy on error removes the temp directory again
+        written?;
+
+        open::that_detached(&copy.file)
+            .map_err(|e| format!("Failed to open working copy: {:?}", e))?;
+        Ok(copy)
+    }
+
+    // Encrypted file the copy saves back to
+    pub fn source(&self) -> &Path {
+        &self.source
+    }
+
+    pub fn file_name(&self) -> String {
+        self.file
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    // Re-encrypt back to the source if the editor saved since the last check
+    pub fn sync(&mut self) -> Result<bool, String> {
+        let current = modified(&self.file);
+        if current.is_none() || current == self.modified {
+            return Ok(false);
+        }
+
+        let plaintext =
+            fs::read(&self.file).map_err(|e| format!("Failed to read working copy: {:?}", e))?;
+        let encrypted = encrypt_with_backend(&plaintext, self.backend)?;
+
+        // Replace the source atomically so a crash never leaves it half written
+        let tmp = self.source.with_extension("lockbox-tmp");
+        fs::write(&tmp, encrypted)
+            .and_then(|_| fs::rename(&tmp, &self.source))
+            .map_err(|e| format!("Failed to write encrypted file: {:?}", e))?;
+
+        self.modified = current;
+        Ok(true)
+    }
+
+    // Shred the copy and anything the editor left beside it, then remove the directory
+    pub fn close(self) -> Result<(), String> {
+        wipe(&self.dir)
+    }
+}
+
+impl Drop for WorkingCopy {
+    // Last resort when the copy was not closed; there is nobody to report errors to
+    fn drop(&mut self) {
+        if self.dir.exists() {
+            let _ = wipe(&self.dir);
+        }
+    }
+}
+
+fn wipe(dir: &Path) -> Result<(), String> {
+    // Editors may leave backup or swap files next to the copy
+    let mut result = Ok(());
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_file() {
+                if let Err(e) = shred::shred(&path, 1) {
+                    result = Err(e);
+                }
+            }
+        }
+    }
+    fs::remove_dir_all(dir).map_err(|e| format!("Failed to remove working copy: {:?}", e))?;
+    result
+}
+
+// Prefer memory-backed locations so plaintext never reaches the disk
+fn temp_root() -> PathBuf {
+    let shm = Path::new("/dev/shm");
+    if shm.is_dir() {
+        return shm.to_path_buf();
+    }
+    dirs::runtime_dir().unwrap_or_else(std::env::temp_dir)
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
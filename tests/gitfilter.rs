@@ -0,0 +1,108 @@
+// tests/gitfilter.rs
+
+// Runs the real binary as git's clean/smudge filter through a push and a clone
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+const AGE_HEADER: &[u8] = b"age-encryption.org/v1";
+
+fn run(dir: &Path, program: &str, args: &[&str]) -> Vec<u8> {
+    let output = Command::new(program)
+        .args(args)
+        .current_dir(dir)
+        // Keep the user's git config and commit identity out of the test
+        .env("HOME", dir)
+        .env("GIT_CONFIG_NOSYSTEM", "1")
+        .env("GIT_AUTHOR_NAME", "lockbox")
+        .env("GIT_AUTHOR_EMAIL", "lockbox@example.com")
+        .env("GIT_COMMITTER_NAME", "lockbox")
+        .env("GIT_COMMITTER_EMAIL", "lockbox@example.com")
+        .output()
+        .unwrap_or_else(|e| panic!("Failed to run {}: {:?}", program, e));
+    assert!(
+        output.status.success(),
+        "{} {:?} failed: {}",
+        program,
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    output.stdout
+}
+
+fn git(dir: &Path, args: &[&str]) -> Vec<u8> {
+    run(dir, "git", args)
+}
+
+fn lockbox(dir: &Path, args: &[&str]) -> Vec<u8> {
+    run(dir, env!("CARGO_BIN_EXE_lockbox"), args)
+}
+
+#[test]
+fn files_are_encrypted_in_the_remote_and_decrypted_in_a_clone() {
+    let root = tempfile::tempdir().unwrap();
+    let (remote, work, clone) = (
+        root.path().join("remote.git"),
+        root.path().join("work"),
+        root.path().join("clone"),
+    );
+    fs::create_dir(&work).unwrap();
+
+    git(root.path(), &["init", "--bare", "-q", "remote.git"]);
+    git(&work, &["init", "-q"]);
+    lockbox(&work, &["git", "init", "*.env"]);
+    fs::write(work.join("app.env"), "API_KEY=secret\n").unwrap();
+    fs::write(work.join("README"), "public\n").unwrap();
+    git(&work, &["add", "."]);
+    git(&work, &["commit", "-q", "-m", "Add secrets"]);
+    git(
+        &work,
+        &[
+            "push",
+            "-q",
+            remote.to_str().unwrap(),
+            "HEAD:refs/heads/main",
+        ],
+    );
+
+    // The remote only ever sees ciphertext for filtered files
+    let blob = git(&remote, &["show", "main:app.env"]);
+    assert!(blob.starts_with(AGE_HEADER));
+    assert_eq!(git(&remote, &["show", "main:README"]), b"public\n");
+
+    // Cleaning again reuses the cached ciphertext, so the work tree stays clean
+    assert!(git(&work, &["status", "--porcelain"]).is_empty());
+
+    // A clone without the key checks out ciphertext
+    git(
+        root.path(),
+        &[
+            "clone",
+            "-q",
+            "-b",
+            "main",
+            remote.to_str().unwrap(),
+            "clone",
+        ],
+    );
+    assert!(fs::read(clone.join("app.env"))
+        .unwrap()
+        .starts_with(AGE_HEADER));
+
+    // With the shared key the filter smudges the plaintext back
+    fs::create_dir_all(clone.join(".git/lockbox")).unwrap();
+    fs::copy(
+        work.join(".git/lockbox/identity"),
+        clone.join(".git/lockbox/identity"),
+    )
+    .unwrap();
+    lockbox(&clone, &["git", "init"]);
+    fs::remove_file(clone.join("app.env")).unwrap();
+    git(&clone, &["checkout", "--", "app.env"]);
+    assert_eq!(
+        fs::read(clone.join("app.env")).unwrap(),
+        b"API_KEY=secret\n"
+    );
+    assert!(git(&clone, &["status", "--porcelain"]).is_empty());
+}